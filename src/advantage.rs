@@ -0,0 +1,161 @@
+use crate::{
+    payout::{bonus_value, expected_blackjack},
+    types::{GameType, IsDoubleAllowed, Rules, SplitAces},
+};
+
+/// Player advantage gained per Hi-Lo true count
+pub const ADVANTAGE_PER_TRUE_COUNT: f64 = 0.005;
+
+/// Player blackjacks per hand that aren't pushed by a dealer blackjack
+const UNPUSHED_BLACKJACK_FREQUENCY: f64 = 0.0453;
+
+/// Off-the-top house edge as a fraction of the initial bet, built up from the commonly quoted
+/// effect of each rule starting from a single deck S17 game paying 3:2 with no double after split
+pub fn house_edge(rules: &Rules) -> f64 {
+    let mut edge = match rules.decks() {
+        0 | 1 => 0.0,
+        2 => 0.0032,
+        3 => 0.0043,
+        4 => 0.0048,
+        5 => 0.0052,
+        6 => 0.0054,
+        7 => 0.0056,
+        _ => 0.0057,
+    };
+
+    if rules.game_type() == &GameType::Hit17 {
+        edge += 0.0022;
+    }
+
+    if rules.double_after_split() {
+        edge -= 0.0014;
+    }
+
+    if rules.surrender() {
+        edge -= 0.0008;
+    }
+
+    edge += match rules.split_aces() {
+        SplitAces::No => 0.0018,
+        SplitAces::SplitAcesOnce => 0.0,
+        SplitAces::ReSplitAces => -0.0008,
+    };
+
+    edge -= UNPUSHED_BLACKJACK_FREQUENCY * (expected_blackjack(rules) - 1.5);
+    edge -= bonus_value(rules);
+
+    edge += match rules.is_double_allowed() {
+        IsDoubleAllowed::Any => 0.0,
+        IsDoubleAllowed::NineTenAndAceOnly => 0.0009,
+        IsDoubleAllowed::NineAndTenOnly => 0.0018,
+        IsDoubleAllowed::TenAndElevenOnly => 0.0026,
+        IsDoubleAllowed::No => 0.016,
+    };
+
+    if rules.no_hole_card() {
+        edge += 0.0011;
+    }
+
+    edge += match rules.max_splits_allowed() {
+        0 => 0.0045,
+        1 => 0.0005,
+        2 => 0.0001,
+        _ => 0.0,
+    };
+
+    edge
+}
+
+/// Player advantage at the true count (in the configured counting system) as a fraction of the
+/// initial bet
+pub fn advantage(rules: &Rules, true_count: isize) -> f64 {
+    let true_count = rules.effective_true_count(true_count) as f64
+        / rules.counting_system().system().index_scale() as f64;
+
+    true_count * ADVANTAGE_PER_TRUE_COUNT - house_edge(rules)
+}
+
+/// Variance of a single hand in squared units
+pub fn variance(rules: &Rules) -> f64 {
+    if rules.double_after_split() {
+        1.33
+    } else {
+        1.26
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{BlackjackPayout, CountingSystemType, Ratio, RulesBuilder};
+
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn six_deck_h17_das_ls() {
+        let rules = RulesBuilder::new()
+            .decks(6)
+            .game_type(GameType::Hit17)
+            .double_after_split(true)
+            .surrender(true)
+            .build();
+
+        assert!(approx(
+            house_edge(&rules),
+            0.0054 + 0.0022 - 0.0014 - 0.0008
+        ));
+    }
+
+    #[test]
+    fn six_to_five_costs_more_than_a_percent() {
+        let three_to_two = RulesBuilder::new().build();
+        let six_to_five = RulesBuilder::new()
+            .blackjack_payout(BlackjackPayout::SixToFive)
+            .build();
+
+        assert!(house_edge(&six_to_five) - house_edge(&three_to_two) > 0.01);
+    }
+
+    #[test]
+    fn custom_payouts_fall_between_the_named_ones() {
+        let edge = |payout| house_edge(&RulesBuilder::new().blackjack_payout(payout).build());
+
+        let seven_to_five = edge(BlackjackPayout::Custom {
+            payout: Ratio::new(7, 5),
+            suited: Ratio::new(7, 5),
+        });
+
+        assert!(edge(BlackjackPayout::ThreeToTwo) < seven_to_five);
+        assert!(seven_to_five < edge(BlackjackPayout::SixToFive));
+        assert!(approx(
+            seven_to_five,
+            edge(BlackjackPayout::SixToFiveSuitedTwoToOne)
+        ));
+    }
+
+    #[test]
+    fn advantage_grows_with_the_count() {
+        let rules = RulesBuilder::new().build();
+
+        assert!(approx(advantage(&rules, 0), -house_edge(&rules)));
+        assert!(approx(
+            advantage(&rules, 4) - advantage(&rules, 2),
+            2.0 * ADVANTAGE_PER_TRUE_COUNT
+        ));
+        assert!(advantage(&rules, 2) > 0.0);
+    }
+
+    #[test]
+    fn advantage_in_other_counting_systems() {
+        let hi_lo = RulesBuilder::new().build();
+        let omega = RulesBuilder::new()
+            .counting_system(CountingSystemType::OmegaII)
+            .build();
+
+        // omega II's true count runs ~1.8 times higher
+        assert!((advantage(&omega, 7) - advantage(&hi_lo, 4)).abs() < 0.001);
+    }
+}
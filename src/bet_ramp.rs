@@ -0,0 +1,146 @@
+/// Maps the true count to a bet in units. Each step is the lowest true count at which its bet
+/// applies, counts below the first step bet the table minimum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BetRamp {
+    steps: Vec<(isize, u32)>,
+    min_bet: u32,
+    max_bet: u32,
+    wong_out_below: Option<isize>,
+}
+
+impl Default for BetRamp {
+    fn default() -> Self {
+        Self::flat()
+    }
+}
+
+impl BetRamp {
+    pub fn new(mut steps: Vec<(isize, u32)>) -> Self {
+        steps.sort_by_key(|(true_count, _)| *true_count);
+
+        Self {
+            steps,
+            min_bet: 1,
+            max_bet: u32::MAX,
+            wong_out_below: None,
+        }
+    }
+
+    /// One unit regardless of the count
+    pub fn flat() -> Self {
+        Self::new(vec![])
+    }
+
+    pub fn one_to_eight() -> Self {
+        Self::new(vec![(2, 2), (3, 4), (4, 6), (5, 8)])
+    }
+
+    pub fn one_to_twelve() -> Self {
+        Self::new(vec![(2, 2), (3, 4), (4, 8), (5, 10), (6, 12)])
+    }
+
+    /// Leaves the table once the true count drops below -1
+    pub fn one_to_sixteen_wonging() -> Self {
+        Self::new(vec![(2, 4), (3, 8), (4, 12), (5, 16)]).wong_out_below(-1)
+    }
+
+    /// Table limits in units, every bet is clamped to them
+    pub fn table_limits(mut self, min_bet: u32, max_bet: u32) -> Self {
+        self.min_bet = min_bet.max(1);
+        self.max_bet = max_bet.max(self.min_bet);
+        self
+    }
+
+    pub fn wong_out_below(mut self, true_count: isize) -> Self {
+        self.wong_out_below = Some(true_count);
+        self
+    }
+
+    /// Bet in units for the true count, `None` when the player should not be playing
+    pub fn bet(&self, true_count: isize) -> Option<u32> {
+        if self.wong_out_below.is_some_and(|tc| true_count < tc) {
+            return None;
+        }
+
+        let units = self
+            .steps
+            .iter()
+            .rev()
+            .find(|(tc, _)| true_count >= *tc)
+            .map(|(_, units)| *units)
+            .unwrap_or(self.min_bet);
+
+        Some(units.clamp(self.min_bet, self.max_bet))
+    }
+
+    /// Largest bet divided by the smallest one
+    pub fn spread(&self) -> u32 {
+        let max = self
+            .steps
+            .iter()
+            .map(|(_, units)| *units)
+            .max()
+            .unwrap_or(self.min_bet)
+            .clamp(self.min_bet, self.max_bet);
+
+        max / self.min_bet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_ramp() {
+        let ramp = BetRamp::flat();
+
+        for tc in -10..=10 {
+            assert_eq!(ramp.bet(tc), Some(1));
+        }
+        assert_eq!(ramp.spread(), 1);
+    }
+
+    #[test]
+    fn one_to_eight() {
+        let ramp = BetRamp::one_to_eight();
+
+        assert_eq!(ramp.bet(-5), Some(1));
+        assert_eq!(ramp.bet(1), Some(1));
+        assert_eq!(ramp.bet(2), Some(2));
+        assert_eq!(ramp.bet(3), Some(4));
+        assert_eq!(ramp.bet(4), Some(6));
+        assert_eq!(ramp.bet(10), Some(8));
+        assert_eq!(ramp.spread(), 8);
+    }
+
+    #[test]
+    fn one_to_twelve() {
+        let ramp = BetRamp::one_to_twelve();
+
+        assert_eq!(ramp.bet(0), Some(1));
+        assert_eq!(ramp.bet(4), Some(8));
+        assert_eq!(ramp.bet(7), Some(12));
+        assert_eq!(ramp.spread(), 12);
+    }
+
+    #[test]
+    fn wonging() {
+        let ramp = BetRamp::one_to_sixteen_wonging();
+
+        assert_eq!(ramp.bet(-2), None);
+        assert_eq!(ramp.bet(-1), Some(1));
+        assert_eq!(ramp.bet(5), Some(16));
+        assert_eq!(ramp.spread(), 16);
+    }
+
+    #[test]
+    fn table_limits() {
+        let ramp = BetRamp::one_to_sixteen_wonging().table_limits(2, 10);
+
+        assert_eq!(ramp.bet(0), Some(2));
+        assert_eq!(ramp.bet(3), Some(8));
+        assert_eq!(ramp.bet(6), Some(10));
+        assert_eq!(ramp.spread(), 5);
+    }
+}
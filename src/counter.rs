@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    shoe::CARDS_PER_DECK,
+    types::{CountingSystemType, Rules},
+};
+
+/// How precisely the discard tray (or rather the remaining shoe) is estimated when converting
+/// the running count to a true count
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DeckEstimation {
+    FullDeck,
+    #[default]
+    HalfDeck,
+    QuarterDeck,
+    Exact,
+}
+
+impl DeckEstimation {
+    pub fn decks_remaining(&self, cards_remaining: usize) -> f32 {
+        let decks = cards_remaining as f32 / CARDS_PER_DECK as f32;
+
+        let step = match self {
+            DeckEstimation::FullDeck => 1.0,
+            DeckEstimation::HalfDeck => 0.5,
+            DeckEstimation::QuarterDeck => 0.25,
+            DeckEstimation::Exact => return decks,
+        };
+
+        ((decks / step).round() * step).max(step)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TrueCountRounding {
+    #[default]
+    Floor,
+    Truncate,
+    Round,
+}
+
+impl TrueCountRounding {
+    pub fn apply(&self, true_count: f32) -> isize {
+        match self {
+            TrueCountRounding::Floor => true_count.floor() as isize,
+            TrueCountRounding::Truncate => true_count.trunc() as isize,
+            TrueCountRounding::Round => true_count.round() as isize,
+        }
+    }
+}
+
+/// True count taken by the decision functions. Whole counts are used as they are, fractional ones
+/// are rounded by each index play with the convention its index was computed for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct TrueCount(f32);
+
+impl TrueCount {
+    pub fn new(value: f32) -> Self {
+        Self(value)
+    }
+
+    /// Fixed point count of `value / scale`, such as 27 and 10 for +2.7
+    pub fn fixed(value: isize, scale: isize) -> Self {
+        Self(value as f32 / scale.max(1) as f32)
+    }
+
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+
+    pub fn rounded(&self, rounding: TrueCountRounding) -> isize {
+        rounding.apply(self.0)
+    }
+}
+
+impl From<isize> for TrueCount {
+    fn from(value: isize) -> Self {
+        Self(value as f32)
+    }
+}
+
+impl From<i32> for TrueCount {
+    fn from(value: i32) -> Self {
+        Self(value as f32)
+    }
+}
+
+impl From<f32> for TrueCount {
+    fn from(value: f32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<f64> for TrueCount {
+    fn from(value: f64) -> Self {
+        Self(value as f32)
+    }
+}
+
+/// Keeps the count for a shoe. Every card that is seen (player, dealer, other players and burn
+/// cards) should be passed to `observe`.
+#[derive(Clone, Debug)]
+pub struct Counter {
+    counting_system: CountingSystemType,
+    total_cards: usize,
+    initial_running_count: isize,
+    running_count: isize,
+    cards_seen: usize,
+    deck_estimation: DeckEstimation,
+    rounding: TrueCountRounding,
+}
+
+impl Counter {
+    pub fn new(rules: &Rules) -> Self {
+        Self {
+            counting_system: rules.counting_system(),
+            total_cards: rules.decks() as usize * CARDS_PER_DECK,
+            initial_running_count: rules.initial_running_count(),
+            running_count: rules.initial_running_count(),
+            cards_seen: 0,
+            deck_estimation: DeckEstimation::default(),
+            rounding: TrueCountRounding::default(),
+        }
+    }
+
+    pub fn observe(&mut self, card: u8) {
+        self.running_count += self.counting_system.system().tag(card);
+        self.cards_seen += 1;
+    }
+
+    pub fn observe_all(&mut self, cards: &[u8]) {
+        for &card in cards {
+            self.observe(card);
+        }
+    }
+
+    /// Start of a new shoe
+    pub fn reset(&mut self) {
+        self.running_count = self.initial_running_count;
+        self.cards_seen = 0;
+    }
+
+    pub fn running_count(&self) -> isize {
+        self.running_count
+    }
+
+    pub fn true_count(&self) -> isize {
+        self.rounding.apply(self.exact_true_count())
+    }
+
+    /// True count before rounding, still subject to the deck estimation
+    pub fn exact_true_count(&self) -> f32 {
+        self.counting_system
+            .system()
+            .true_count(self.running_count, self.decks_remaining())
+    }
+
+    pub fn decks_remaining(&self) -> f32 {
+        self.deck_estimation
+            .decks_remaining(self.total_cards.saturating_sub(self.cards_seen))
+    }
+
+    pub fn cards_seen(&self) -> usize {
+        self.cards_seen
+    }
+
+    pub fn counting_system(&self) -> CountingSystemType {
+        self.counting_system
+    }
+}
+
+// setters
+impl Counter {
+    pub fn set_deck_estimation(&mut self, value: DeckEstimation) {
+        self.deck_estimation = value;
+    }
+
+    pub fn set_rounding(&mut self, value: TrueCountRounding) {
+        self.rounding = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hard_totals_chart::hard_totals_chart,
+        types::{Decision, Deviations, RulesBuilder},
+    };
+
+    use super::*;
+
+    #[test]
+    fn hi_lo_running_count() {
+        let mut counter = Counter::new(&Rules::default());
+
+        counter.observe_all(&[2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(counter.running_count(), 3);
+        assert_eq!(counter.cards_seen(), 10);
+
+        counter.reset();
+        assert_eq!(counter.running_count(), 0);
+        assert_eq!(counter.cards_seen(), 0);
+    }
+
+    #[test]
+    fn unbalanced_starts_at_initial_running_count() {
+        let rules = RulesBuilder::new()
+            .decks(6)
+            .counting_system(CountingSystemType::KnockOut)
+            .build();
+        let mut counter = Counter::new(&rules);
+
+        assert_eq!(counter.running_count(), -20);
+
+        counter.observe(7);
+        assert_eq!(counter.running_count(), -19);
+
+        counter.reset();
+        assert_eq!(counter.running_count(), -20);
+    }
+
+    #[test]
+    fn deck_estimation() {
+        assert_eq!(DeckEstimation::FullDeck.decks_remaining(130), 3.0);
+        assert_eq!(DeckEstimation::HalfDeck.decks_remaining(130), 2.5);
+        assert_eq!(DeckEstimation::QuarterDeck.decks_remaining(120), 2.25);
+        assert_eq!(DeckEstimation::Exact.decks_remaining(117), 2.25);
+
+        // never estimates an empty shoe
+        assert_eq!(DeckEstimation::FullDeck.decks_remaining(5), 1.0);
+        assert_eq!(DeckEstimation::HalfDeck.decks_remaining(0), 0.5);
+    }
+
+    #[test]
+    fn true_count_rounding() {
+        assert_eq!(TrueCountRounding::Floor.apply(-1.5), -2);
+        assert_eq!(TrueCountRounding::Truncate.apply(-1.5), -1);
+        assert_eq!(TrueCountRounding::Round.apply(2.6), 3);
+        assert_eq!(TrueCountRounding::Floor.apply(2.6), 2);
+    }
+
+    #[test]
+    fn fractional_true_counts() {
+        assert_eq!(TrueCount::fixed(27, 10).value(), 2.7);
+        assert_eq!(TrueCount::from(-3).value(), -3.0);
+        assert_eq!(TrueCount::from(2.5).rounded(TrueCountRounding::Round), 3);
+        assert_eq!(
+            TrueCount::fixed(-15, 10).rounded(TrueCountRounding::Floor),
+            -2
+        );
+    }
+
+    #[test]
+    fn true_count_from_observed_cards() {
+        let rules = RulesBuilder::new().decks(2).build();
+        let mut counter = Counter::new(&rules);
+        counter.set_deck_estimation(DeckEstimation::Exact);
+
+        // 26 small cards out of 104, running count +26 with 1.5 decks left
+        counter.observe_all(&[2; 26]);
+        assert_eq!(counter.running_count(), 26);
+        assert_eq!(counter.decks_remaining(), 1.5);
+        assert_eq!(counter.true_count(), 17);
+
+        counter.set_rounding(TrueCountRounding::Round);
+        assert_eq!(counter.true_count(), 17);
+
+        counter.observe_all(&[10; 40]);
+        // -14 with 38 cards left
+        assert_eq!(counter.running_count(), -14);
+
+        counter.set_rounding(TrueCountRounding::Truncate);
+        assert_eq!(counter.true_count(), -19);
+
+        counter.set_rounding(TrueCountRounding::Floor);
+        assert_eq!(counter.true_count(), -20);
+    }
+
+    #[test]
+    fn counter_plugs_into_charts() {
+        let mut rules = Rules::default();
+        rules.set_enable_deviations(Deviations::Standard);
+
+        let mut counter = Counter::new(&rules);
+        counter.observe_all(&[2, 3, 4, 5, 6, 2, 3, 4, 5, 6, 2, 3]);
+
+        assert_eq!(counter.true_count(), 2);
+        assert_eq!(
+            hard_totals_chart(
+                &vec![10, 2],
+                3,
+                counter.running_count(),
+                counter.true_count(),
+                &rules
+            ),
+            Some(Decision::Stand)
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::time::Duration;
+
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{
+    counter::{DeckEstimation, TrueCountRounding},
+    shoe::{CARDS_PER_DECK, unshuffled_cards},
+    types::{CountingSystemType, Rules},
+};
+
+/// What the player is shown
+#[derive(Clone, Debug, PartialEq)]
+pub enum Prompt {
+    /// Count every card, starting from zero
+    Countdown(Vec<u8>),
+    /// Net count of a small group of cards, for practicing cancellation
+    Cancellation(Vec<u8>),
+    /// Convert the running count to a true count by estimating the discard tray
+    TrueCount {
+        running_count: isize,
+        cards_discarded: usize,
+        decks: u8,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DrillResult {
+    pub correct: bool,
+    pub expected: isize,
+    pub answer: isize,
+    pub elapsed: Duration,
+}
+
+/// A single counting question. The caller times the answer, so drills work where there is no
+/// system clock such as `wasm32-unknown-unknown`.
+#[derive(Clone, Debug)]
+pub struct Drill {
+    prompt: Prompt,
+    expected: isize,
+    counting_system: CountingSystemType,
+}
+
+impl Drill {
+    fn new(prompt: Prompt, expected: isize, counting_system: CountingSystemType) -> Self {
+        Self {
+            prompt,
+            expected,
+            counting_system,
+        }
+    }
+
+    /// Deals `cards` cards from a shuffled deck, a full deck countdown ends at the system's
+    /// imbalance
+    pub fn deck_countdown<R: Rng + ?Sized>(
+        counting_system: CountingSystemType,
+        cards: usize,
+        rng: &mut R,
+    ) -> Self {
+        let mut deck = unshuffled_cards(1);
+        deck.shuffle(rng);
+        deck.truncate(cards.clamp(1, CARDS_PER_DECK));
+
+        let expected = count(counting_system, &deck);
+
+        Self::new(Prompt::Countdown(deck), expected, counting_system)
+    }
+
+    /// Pairs or triples of cards, `group_size` is clamped to 2 and 3
+    pub fn cancellation<R: Rng + ?Sized>(
+        counting_system: CountingSystemType,
+        group_size: usize,
+        rng: &mut R,
+    ) -> Self {
+        let mut deck = unshuffled_cards(1);
+        deck.shuffle(rng);
+        deck.truncate(group_size.clamp(2, 3));
+
+        let expected = count(counting_system, &deck);
+
+        Self::new(Prompt::Cancellation(deck), expected, counting_system)
+    }
+
+    /// Running count and discard tray for the rules' shoe, at least half a deck is left. The
+    /// expected answer uses the given deck estimation and rounding.
+    pub fn true_count_conversion<R: Rng + ?Sized>(
+        rules: &Rules,
+        deck_estimation: DeckEstimation,
+        rounding: TrueCountRounding,
+        rng: &mut R,
+    ) -> Self {
+        let counting_system = rules.counting_system();
+        let system = counting_system.system();
+
+        let total_cards = rules.decks() as usize * CARDS_PER_DECK;
+        let cards_discarded = rng.random_range(0..=total_cards - CARDS_PER_DECK / 2);
+        let cards_remaining = total_cards - cards_discarded;
+
+        // up to a true count of +-6 around the pivot
+        let spread = (6.0 * cards_remaining as f32 / CARDS_PER_DECK as f32).round() as i64;
+        let running_count = system.pivot() + rng.random_range(-spread..=spread) as isize;
+
+        let decks_remaining = deck_estimation.decks_remaining(cards_remaining);
+        let expected = rounding.apply(system.true_count(running_count, decks_remaining));
+
+        let prompt = Prompt::TrueCount {
+            running_count,
+            cards_discarded,
+            decks: rules.decks(),
+        };
+
+        Self::new(prompt, expected, counting_system)
+    }
+
+    pub fn prompt(&self) -> &Prompt {
+        &self.prompt
+    }
+
+    pub fn expected(&self) -> isize {
+        self.expected
+    }
+
+    pub fn counting_system(&self) -> CountingSystemType {
+        self.counting_system
+    }
+
+    /// Grades the answer given `elapsed` after the prompt was shown
+    pub fn answer(&self, answer: isize, elapsed: Duration) -> DrillResult {
+        DrillResult {
+            correct: answer == self.expected,
+            expected: self.expected,
+            answer,
+            elapsed,
+        }
+    }
+}
+
+/// Accuracy and speed over a number of drills
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DrillStats {
+    attempts: usize,
+    correct: usize,
+    total_time: Duration,
+    fastest_correct: Option<Duration>,
+}
+
+impl DrillStats {
+    pub fn record(&mut self, result: &DrillResult) {
+        self.attempts += 1;
+        self.total_time += result.elapsed;
+
+        if result.correct {
+            self.correct += 1;
+            self.fastest_correct = Some(
+                self.fastest_correct
+                    .map_or(result.elapsed, |fastest| fastest.min(result.elapsed)),
+            );
+        }
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    pub fn correct(&self) -> usize {
+        self.correct
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+
+        self.correct as f64 / self.attempts as f64
+    }
+
+    pub fn average_time(&self) -> Duration {
+        if self.attempts == 0 {
+            return Duration::ZERO;
+        }
+
+        self.total_time / self.attempts as u32
+    }
+
+    pub fn fastest_correct(&self) -> Option<Duration> {
+        self.fastest_correct
+    }
+}
+
+fn count(counting_system: CountingSystemType, cards: &[u8]) -> isize {
+    let system = counting_system.system();
+
+    cards.iter().map(|card| system.tag(*card)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{rng::seeded_rng, types::RulesBuilder};
+
+    use super::*;
+
+    #[test]
+    fn full_deck_countdown_ends_at_the_imbalance() {
+        let mut rng = seeded_rng(1);
+
+        for counting_system in [
+            CountingSystemType::HiLo,
+            CountingSystemType::KnockOut,
+            CountingSystemType::OmegaII,
+        ] {
+            let drill = Drill::deck_countdown(counting_system, 52, &mut rng);
+
+            assert_eq!(
+                drill.expected(),
+                counting_system.system().imbalance_per_deck()
+            );
+            assert!(matches!(drill.prompt(), Prompt::Countdown(cards) if cards.len() == 52));
+        }
+    }
+
+    #[test]
+    fn partial_countdown() {
+        let drill = Drill::deck_countdown(CountingSystemType::HiLo, 20, &mut seeded_rng(2));
+
+        let Prompt::Countdown(cards) = drill.prompt() else {
+            panic!("expected a countdown");
+        };
+        assert_eq!(cards.len(), 20);
+        assert_eq!(drill.expected(), count(CountingSystemType::HiLo, cards));
+    }
+
+    #[test]
+    fn cancellation_groups() {
+        let mut rng = seeded_rng(3);
+
+        for (group_size, expected_len) in [(1, 2), (2, 2), (3, 3), (5, 3)] {
+            let drill = Drill::cancellation(CountingSystemType::Zen, group_size, &mut rng);
+
+            let Prompt::Cancellation(cards) = drill.prompt() else {
+                panic!("expected a cancellation drill");
+            };
+            assert_eq!(cards.len(), expected_len);
+            assert_eq!(drill.expected(), count(CountingSystemType::Zen, cards));
+        }
+    }
+
+    #[test]
+    fn true_count_conversion() {
+        let rules = RulesBuilder::new().decks(6).build();
+        let mut rng = seeded_rng(4);
+
+        for _ in 0..200 {
+            let drill = Drill::true_count_conversion(
+                &rules,
+                DeckEstimation::HalfDeck,
+                TrueCountRounding::Floor,
+                &mut rng,
+            );
+
+            let Prompt::TrueCount {
+                running_count,
+                cards_discarded,
+                decks,
+            } = *drill.prompt()
+            else {
+                panic!("expected a true count drill");
+            };
+
+            assert_eq!(decks, 6);
+            assert!(cards_discarded <= 6 * 52 - 26);
+
+            let decks_remaining = DeckEstimation::HalfDeck.decks_remaining(312 - cards_discarded);
+            assert_eq!(
+                drill.expected(),
+                (running_count as f32 / decks_remaining).floor() as isize
+            );
+            assert!(drill.expected().abs() <= 8);
+        }
+    }
+
+    #[test]
+    fn timed_results() {
+        let drill = Drill::deck_countdown(CountingSystemType::HiLo, 10, &mut seeded_rng(5));
+        let mut stats = DrillStats::default();
+
+        stats.record(&drill.answer(drill.expected(), Duration::from_secs(4)));
+        stats.record(&drill.answer(drill.expected() + 1, Duration::from_secs(2)));
+        stats.record(&drill.answer(drill.expected(), Duration::from_secs(6)));
+
+        assert_eq!(stats.attempts(), 3);
+        assert_eq!(stats.correct(), 2);
+        assert!((stats.accuracy() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.average_time(), Duration::from_secs(4));
+        assert_eq!(stats.fastest_correct(), Some(Duration::from_secs(4)));
+    }
+}
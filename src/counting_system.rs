@@ -0,0 +1,400 @@
+/// A card counting system. Cards are represented the same way as everywhere else in the crate:
+/// `2..=10` by value and `11` for an ace.
+pub trait CountingSystem {
+    fn name(&self) -> &'static str;
+
+    /// Tag value for a single card. Systems with half-point tags (Wong Halves) are expressed in
+    /// doubled units so that the running count stays integral.
+    fn tag(&self, card: u8) -> isize;
+
+    fn is_balanced(&self) -> bool;
+
+    fn level(&self) -> u8;
+
+    /// How much larger this system's true count runs compared to Hi-Lo, derived from the spread of
+    /// the tags over a full deck. Hi-Lo is exactly `1.0`.
+    fn index_scale(&self) -> f32 {
+        let sum_of_squares: isize = (2..=11)
+            .map(|card| cards_per_deck(card) * self.tag(card) * self.tag(card))
+            .sum();
+
+        (sum_of_squares as f32 / HI_LO_SUM_OF_SQUARES as f32).sqrt()
+    }
+
+    /// Converts a Hi-Lo index into this system's index
+    fn index(&self, hi_lo_index: isize) -> isize {
+        (hi_lo_index as f32 * self.index_scale()).round() as isize
+    }
+
+    /// Sum of the tags over a full deck, zero for balanced systems
+    fn imbalance_per_deck(&self) -> isize {
+        (2..=11)
+            .map(|card| cards_per_deck(card) * self.tag(card))
+            .sum()
+    }
+
+    /// Running count at which an unbalanced count is worth `imbalance_per_deck` true count
+    /// regardless of how many decks are left
+    fn pivot(&self) -> isize {
+        0
+    }
+
+    fn initial_running_count(&self, decks: u8) -> isize {
+        self.pivot() - self.imbalance_per_deck() * decks as isize
+    }
+
+    /// Running count at which the player gains the edge and should start raising bets
+    fn key_count(&self, decks: u8) -> isize {
+        nominal_key_count(self, decks)
+    }
+
+    /// Running count with the built-in drift of an unbalanced system removed
+    fn balanced_running_count(&self, running_count: isize, decks_remaining: f32) -> isize {
+        running_count - self.pivot()
+            + (self.imbalance_per_deck() as f32 * decks_remaining).round() as isize
+    }
+
+    /// Unrounded true count, for an unbalanced system this is the true count of the equivalent
+    /// balanced count offset by `imbalance_per_deck`
+    fn true_count(&self, running_count: isize, decks_remaining: f32) -> f32 {
+        let decks_remaining = decks_remaining.max(MIN_DECKS_REMAINING);
+
+        self.imbalance_per_deck() as f32 + (running_count - self.pivot()) as f32 / decks_remaining
+    }
+}
+
+const MIN_DECKS_REMAINING: f32 = 0.25;
+
+const HI_LO_SUM_OF_SQUARES: isize = 40;
+
+fn cards_per_deck(card: u8) -> isize {
+    if card == 10 { 16 } else { 4 }
+}
+
+/// Half of the shoe is assumed to be left when the actual number of decks remaining is unknown
+pub fn nominal_decks_remaining(decks: u8) -> f32 {
+    (decks as f32 / 2.0).max(0.5)
+}
+
+fn nominal_key_count<T: CountingSystem + ?Sized>(system: &T, decks: u8) -> isize {
+    let edge_index = system.index(1) - system.imbalance_per_deck();
+
+    system.pivot() + (edge_index as f32 * nominal_decks_remaining(decks)).round() as isize
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HiLo;
+
+impl CountingSystem for HiLo {
+    fn name(&self) -> &'static str {
+        "Hi-Lo"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2..=6 => 1,
+            10 | 11 => -1,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    fn level(&self) -> u8 {
+        1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KnockOut;
+
+impl CountingSystem for KnockOut {
+    fn name(&self) -> &'static str {
+        "KO"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2..=7 => 1,
+            10 | 11 => -1,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        false
+    }
+
+    fn level(&self) -> u8 {
+        1
+    }
+
+    fn pivot(&self) -> isize {
+        4
+    }
+
+    /// Published key counts, the nominal formula for other shoe sizes
+    fn key_count(&self, decks: u8) -> isize {
+        match decks {
+            1 => 2,
+            2 => 1,
+            6 => -4,
+            8 => -6,
+            _ => nominal_key_count(self, decks),
+        }
+    }
+}
+
+/// Only red sevens count, which is modelled by doubling every tag and counting each seven as
+/// +1, the average of a red (+2) and a black (0) seven
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RedSeven;
+
+impl CountingSystem for RedSeven {
+    fn name(&self) -> &'static str {
+        "Red 7"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2..=6 => 2,
+            7 => 1,
+            10 | 11 => -2,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        false
+    }
+
+    fn level(&self) -> u8 {
+        1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HiOptI;
+
+impl CountingSystem for HiOptI {
+    fn name(&self) -> &'static str {
+        "Hi-Opt I"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            3..=6 => 1,
+            10 => -1,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    fn level(&self) -> u8 {
+        1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HiOptII;
+
+impl CountingSystem for HiOptII {
+    fn name(&self) -> &'static str {
+        "Hi-Opt II"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2 | 3 | 6 | 7 => 1,
+            4 | 5 => 2,
+            10 => -2,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    fn level(&self) -> u8 {
+        2
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OmegaII;
+
+impl CountingSystem for OmegaII {
+    fn name(&self) -> &'static str {
+        "Omega II"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2 | 3 | 7 => 1,
+            4..=6 => 2,
+            9 => -1,
+            10 => -2,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    fn level(&self) -> u8 {
+        2
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Zen;
+
+impl CountingSystem for Zen {
+    fn name(&self) -> &'static str {
+        "Zen"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2 | 3 | 7 => 1,
+            4..=6 => 2,
+            10 => -2,
+            11 => -1,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    fn level(&self) -> u8 {
+        2
+    }
+}
+
+/// Tags are doubled: 5 is +3 instead of +1.5, 9 is -1 instead of -0.5 and so on
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WongHalves;
+
+impl CountingSystem for WongHalves {
+    fn name(&self) -> &'static str {
+        "Wong Halves"
+    }
+
+    fn tag(&self, card: u8) -> isize {
+        match card {
+            2 | 7 => 1,
+            3 | 4 | 6 => 2,
+            5 => 3,
+            9 => -1,
+            10 | 11 => -2,
+            _ => 0,
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    fn level(&self) -> u8 {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck_total(system: &dyn CountingSystem) -> isize {
+        (2..=11)
+            .map(|card| cards_per_deck(card) * system.tag(card))
+            .sum()
+    }
+
+    #[test]
+    fn balanced_systems_sum_to_zero() {
+        let systems: Vec<&dyn CountingSystem> =
+            vec![&HiLo, &HiOptI, &HiOptII, &OmegaII, &Zen, &WongHalves];
+
+        for system in systems {
+            assert!(system.is_balanced());
+            assert_eq!(deck_total(system), 0, "{}", system.name());
+        }
+    }
+
+    #[test]
+    fn knock_out_is_unbalanced() {
+        assert!(!KnockOut.is_balanced());
+        assert_eq!(deck_total(&KnockOut), 4);
+        assert_eq!(KnockOut.imbalance_per_deck(), 4);
+    }
+
+    #[test]
+    fn unbalanced_initial_running_counts() {
+        assert_eq!(KnockOut.initial_running_count(1), 0);
+        assert_eq!(KnockOut.initial_running_count(2), -4);
+        assert_eq!(KnockOut.initial_running_count(6), -20);
+        assert_eq!(KnockOut.initial_running_count(8), -28);
+
+        // doubled units, -2 per deck
+        assert_eq!(RedSeven.initial_running_count(6), -24);
+
+        assert_eq!(HiLo.initial_running_count(6), 0);
+    }
+
+    #[test]
+    fn unbalanced_key_counts() {
+        assert_eq!(KnockOut.key_count(1), 2);
+        assert_eq!(KnockOut.key_count(2), 1);
+        assert_eq!(KnockOut.key_count(6), -4);
+        assert_eq!(KnockOut.key_count(8), -6);
+        assert_eq!(KnockOut.key_count(4), -2);
+    }
+
+    #[test]
+    fn pivot_is_worth_the_imbalance_at_any_depth() {
+        for decks_remaining in [0.5, 1.0, 2.5, 4.0, 6.0] {
+            assert_eq!(KnockOut.true_count(4, decks_remaining), 4.0);
+            assert_eq!(RedSeven.true_count(0, decks_remaining), 4.0);
+        }
+    }
+
+    #[test]
+    fn balanced_running_count_removes_drift() {
+        // six deck shoe with three decks dealt and no small card excess
+        let running_count = KnockOut.initial_running_count(6) + 4 * 3;
+
+        assert_eq!(KnockOut.balanced_running_count(running_count, 3.0), 0);
+        assert_eq!(KnockOut.true_count(running_count, 3.0), 0.0);
+
+        assert_eq!(HiLo.balanced_running_count(5, 3.0), 5);
+        assert_eq!(HiLo.true_count(5, 2.0), 2.5);
+        assert_eq!(HiLo.true_count(-5, 2.0), -2.5);
+    }
+
+    #[test]
+    fn hi_lo_indices_are_unchanged() {
+        assert_eq!(HiLo.index_scale(), 1.0);
+
+        for idx in -10..=10 {
+            assert_eq!(HiLo.index(idx), idx);
+        }
+    }
+
+    #[test]
+    fn level_two_indices_are_scaled() {
+        assert_eq!(OmegaII.index(4), 7);
+        assert_eq!(Zen.index(3), 5);
+        assert_eq!(HiOptII.index(4), 7);
+        assert_eq!(OmegaII.index(-1), -2);
+        assert_eq!(HiOptI.index(4), 4);
+    }
+}
@@ -1,13 +1,15 @@
 use crate::{
     compute_hand,
-    types::{Decision, GameType, Rules},
+    counter::TrueCount,
+    index_plays::{Action, deviation},
+    types::{Decision, Rules},
 };
 
 pub fn hard_totals_chart(
     cards_in_hand: &Vec<u8>,
     dealer_up_card: u8,
     running_count: isize,
-    true_count: isize,
+    true_count: impl Into<TrueCount>,
     rules: &Rules,
 ) -> Option<Decision> {
     // Only handle certain pairs in hard totals chart (5s always double, 4s have specific logic)
@@ -18,6 +20,26 @@ pub fn hard_totals_chart(
         }
     }
 
+    hard_totals_chart_without_split(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+    )
+}
+
+/// Same as `hard_totals_chart` for hands that won't be split, pairs are played by their total
+pub fn hard_totals_chart_without_split(
+    cards_in_hand: &Vec<u8>,
+    dealer_up_card: u8,
+    running_count: isize,
+    true_count: impl Into<TrueCount>,
+    rules: &Rules,
+) -> Option<Decision> {
+    let (running_count, true_count) =
+        rules.effective_exact_counts(running_count, true_count.into());
+
     let mut tmp = cards_in_hand.clone();
     let (total, res) = compute_hand(&mut tmp);
 
@@ -25,6 +47,31 @@ pub fn hard_totals_chart(
         return None;
     }
 
+    // without a hole card a double against a ten or an ace loses both bets to a blackjack
+    let risks_dealer_blackjack = rules.no_hole_card() && dealer_up_card >= 10;
+    let is_double_allowed = rules.is_double_allowed();
+    let can_double = cards_in_hand.len() == 2
+        && match total {
+            11 => is_double_allowed.double_on_eleven() && !risks_dealer_blackjack,
+            10 => is_double_allowed.double_on_ten() && !risks_dealer_blackjack,
+            9 => is_double_allowed.double_on_nine(),
+            _ => is_double_allowed.any(),
+        };
+
+    match deviation(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+        &[Action::Hit, Action::Stand, Action::Double],
+    ) {
+        Some(Action::Hit) => return Some(Decision::Hit),
+        Some(Action::Stand) => return Some(Decision::Stand),
+        Some(Action::Double) if can_double => return Some(Decision::Double),
+        _ => (),
+    }
+
     if total >= 17
         || (total == 16 && dealer_up_card >= 2 && dealer_up_card <= 6)
         || (total == 15 && dealer_up_card >= 2 && dealer_up_card <= 6)
@@ -33,99 +80,39 @@ pub fn hard_totals_chart(
         return Some(Decision::Stand);
     }
 
-    if total == 16 && rules.enable_deviations() > 0 {
-        if (dealer_up_card == 9 && true_count >= 4)
-            || (dealer_up_card == 10 && running_count > 0)
-            || (dealer_up_card == 11 && true_count >= 3 && rules.game_type() == &GameType::Hit17)
-        {
-            return Some(Decision::Stand);
-        }
-    }
-
-    if total == 15 && rules.enable_deviations() > 0 {
-        if (dealer_up_card == 10 && true_count >= 4)
-            || (dealer_up_card == 11 && true_count >= 5 && rules.game_type() == &GameType::Hit17)
-        {
-            return Some(Decision::Stand);
-        }
-    }
-
-    if total == 13 {
-        if dealer_up_card == 2 && true_count <= -1 && rules.enable_deviations() > 0 {
-            return Some(Decision::Hit);
-        }
-
-        if dealer_up_card >= 2 && dealer_up_card <= 6 {
-            return Some(Decision::Stand);
-        }
+    if total == 13 && dealer_up_card >= 2 && dealer_up_card <= 6 {
+        return Some(Decision::Stand);
     }
 
-    if total == 12 {
-        if dealer_up_card == 4 && running_count < 0 && rules.enable_deviations() > 0 {
-            return Some(Decision::Hit);
-        }
-
-        if (dealer_up_card == 2 && true_count >= 3)
-            || (dealer_up_card == 3 && true_count >= 2) && rules.enable_deviations() > 0
-        {
-            return Some(Decision::Stand);
-        }
-
-        if dealer_up_card >= 4 && dealer_up_card <= 6 {
-            return Some(Decision::Stand);
-        }
+    if total == 12 && dealer_up_card >= 4 && dealer_up_card <= 6 {
+        return Some(Decision::Stand);
     }
 
-    if total == 11 {
-        if rules.enable_deviations() > 0 {
-            match rules.game_type() {
-                GameType::Stand17 if true_count < 1 && dealer_up_card == 11 => {
-                    return Some(Decision::Hit);
-                }
-                _ => (),
-            };
-        }
-
-        if cards_in_hand.len() == 2 {
-            return Some(Decision::Double);
-        }
+    if total == 11 && can_double {
+        return Some(Decision::Double);
     }
 
-    if total == 10 {
-        if ((dealer_up_card >= 2 && dealer_up_card <= 9)
-            || ((dealer_up_card == 10 && true_count >= 4)
-                || (dealer_up_card == 11
-                    && true_count >= 3
-                    && rules.game_type() == &GameType::Hit17)
-                || (dealer_up_card == 11
-                    && true_count >= 4
-                    && rules.game_type() == &GameType::Stand17))
-                && rules.enable_deviations() > 0)
-            && cards_in_hand.len() == 2
-        {
-            return Some(Decision::Double);
-        }
+    if total == 10 && dealer_up_card >= 2 && dealer_up_card <= 9 && can_double {
+        return Some(Decision::Double);
     }
 
-    if total == 9 {
-        if ((dealer_up_card >= 3 && dealer_up_card <= 6)
-            || ((dealer_up_card == 2 && true_count >= 1)
-                || (dealer_up_card == 7 && true_count >= 3))
-                && rules.enable_deviations() > 0)
-            && cards_in_hand.len() == 2
-        {
-            return Some(Decision::Double);
-        }
+    // single and double deck double 9 against a 2
+    if total == 9
+        && ((dealer_up_card >= 3 && dealer_up_card <= 6)
+            || (dealer_up_card == 2 && rules.decks() <= 2))
+        && can_double
+    {
+        return Some(Decision::Double);
     }
 
-    if total == 8 && rules.enable_deviations() > 0 && cards_in_hand.len() == 2 {
-        if dealer_up_card == 6 && true_count >= 2 {
-            return Some(Decision::Double);
-        }
-
-        if dealer_up_card == 5 && true_count >= 4 && rules.enable_deviations() > 1 {
-            return Some(Decision::Double);
-        }
+    // single deck doubles 8 against a 5 or 6, unless it is made of a 6 and a 2
+    if total == 8
+        && rules.decks() == 1
+        && (dealer_up_card == 5 || dealer_up_card == 6)
+        && !cards_in_hand.contains(&6)
+        && can_double
+    {
+        return Some(Decision::Double);
     }
 
     Some(Decision::Hit)
@@ -133,7 +120,11 @@ pub fn hard_totals_chart(
 
 #[cfg(test)]
 mod tests {
-    use crate::types::Deviations;
+    use crate::{
+        counter::TrueCountRounding,
+        index_plays,
+        types::{CountingSystemType, Deviations, GameType, IsDoubleAllowed, RulesBuilder},
+    };
 
     use super::*;
 
@@ -144,6 +135,32 @@ mod tests {
         assert_eq!(hard_totals_chart(&vec![10, 5, 9], 8, 0, 0, &rules), None);
     }
 
+    #[test]
+    fn test_hard_pairs_without_split() {
+        let rules = RulesBuilder::new()
+            .enable_deviations(Deviations::Standard)
+            .build();
+
+        assert_eq!(hard_totals_chart(&vec![8, 8], 10, 1, 0, &rules), None);
+        // played as the hard total, with its index plays
+        assert_eq!(
+            hard_totals_chart_without_split(&vec![8, 8], 10, 1, 0, &rules),
+            Some(Decision::Stand)
+        );
+        assert_eq!(
+            hard_totals_chart_without_split(&vec![8, 8], 10, -1, 0, &rules),
+            Some(Decision::Hit)
+        );
+        assert_eq!(
+            hard_totals_chart_without_split(&vec![6, 6], 4, 0, 0, &rules),
+            Some(Decision::Stand)
+        );
+        assert_eq!(
+            hard_totals_chart_without_split(&vec![3, 3], 6, 0, 0, &rules),
+            Some(Decision::Hit)
+        );
+    }
+
     #[test]
     fn test_hard_17() {
         let rules = Rules::default();
@@ -550,4 +567,235 @@ mod tests {
             Some(Decision::Hit)
         );
     }
+
+    #[test]
+    fn test_hard_deviations_other_counting_system() {
+        let mut rules = Rules::default();
+        rules.set_enable_deviations(Deviations::Standard);
+        rules.set_counting_system(CountingSystemType::OmegaII);
+
+        // hi-lo index of 4 is not enough for omega II
+        assert_eq!(
+            hard_totals_chart(&vec![10, 6], 9, 30, 4, &rules),
+            Some(Decision::Hit)
+        );
+
+        assert_eq!(
+            hard_totals_chart(&vec![10, 6], 9, 30, 7, &rules),
+            Some(Decision::Stand)
+        );
+    }
+
+    #[test]
+    fn test_hard_deviations_unbalanced_counting_system() {
+        let mut rules = Rules::default();
+        rules.set_enable_deviations(Deviations::Standard);
+        rules.set_counting_system(CountingSystemType::KnockOut);
+
+        assert_eq!(rules.initial_running_count(), -20);
+        assert_eq!(rules.key_count(), -4);
+        assert_eq!(rules.pivot(), 4);
+
+        // true count is ignored, the running count at the pivot is worth +4
+        assert_eq!(
+            hard_totals_chart(&vec![10, 6], 9, 3, 10, &rules),
+            Some(Decision::Hit)
+        );
+
+        assert_eq!(
+            hard_totals_chart(&vec![10, 6], 9, 4, 0, &rules),
+            Some(Decision::Stand)
+        );
+
+        // 16 v 10 once the count is above the drift of an average shoe
+        assert_eq!(
+            hard_totals_chart(&vec![10, 6], 10, -8, 0, &rules),
+            Some(Decision::Hit)
+        );
+
+        assert_eq!(
+            hard_totals_chart(&vec![10, 6], 10, -7, 0, &rules),
+            Some(Decision::Stand)
+        );
+    }
+
+    #[test]
+    fn test_hard_double_restrictions() {
+        let rules = RulesBuilder::new()
+            .is_double_allowed(IsDoubleAllowed::TenAndElevenOnly)
+            .build();
+
+        assert_eq!(
+            hard_totals_chart(&vec![5, 4], 5, 0, 0, &rules),
+            Some(Decision::Hit)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![6, 4], 5, 0, 0, &rules),
+            Some(Decision::Double)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![6, 5], 5, 0, 0, &rules),
+            Some(Decision::Double)
+        );
+
+        let rules = RulesBuilder::new()
+            .is_double_allowed(IsDoubleAllowed::No)
+            .build();
+
+        assert_eq!(
+            hard_totals_chart(&vec![6, 5], 5, 0, 0, &rules),
+            Some(Decision::Hit)
+        );
+    }
+
+    #[test]
+    fn test_hard_no_hole_card() {
+        let rules = RulesBuilder::new()
+            .no_hole_card(true)
+            .enable_deviations(Deviations::Standard)
+            .build();
+
+        for duc in [10, 11] {
+            assert_eq!(
+                hard_totals_chart(&vec![6, 5], duc, 0, 0, &rules),
+                Some(Decision::Hit)
+            );
+            assert_eq!(
+                hard_totals_chart(&vec![6, 4], duc, 20, 5, &rules),
+                Some(Decision::Hit)
+            );
+        }
+
+        assert_eq!(
+            hard_totals_chart(&vec![6, 5], 9, 0, 0, &rules),
+            Some(Decision::Double)
+        );
+    }
+
+    #[test]
+    fn test_hard_fractional_true_counts() {
+        let rules = RulesBuilder::new()
+            .enable_deviations(Deviations::Standard)
+            .build();
+
+        // stand at +4 or higher
+        assert_eq!(
+            hard_totals_chart(&vec![10, 5], 10, 0, 3.9, &rules),
+            Some(Decision::Hit)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![10, 5], 10, 0, 4.0, &rules),
+            Some(Decision::Stand)
+        );
+        // hit at -1 or lower
+        assert_eq!(
+            hard_totals_chart(&vec![10, 3], 2, 0, -0.5, &rules),
+            Some(Decision::Stand)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![10, 3], 2, 0, -1.0, &rules),
+            Some(Decision::Hit)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![10, 3], 2, 0, TrueCount::fixed(5, 10), &rules),
+            Some(Decision::Stand)
+        );
+
+        let rounded = RulesBuilder::new()
+            .custom_index_plays(vec![
+                index_plays::STAND_15_V_10.rounding(TrueCountRounding::Round),
+            ])
+            .build();
+
+        assert_eq!(
+            hard_totals_chart(&vec![10, 5], 10, 0, 3.5, &rounded),
+            Some(Decision::Stand)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![10, 5], 10, 0, 3.4, &rounded),
+            Some(Decision::Hit)
+        );
+    }
+
+    #[test]
+    fn test_hard_named_deviation_sets() {
+        let none = Rules::default();
+        let standard = RulesBuilder::new()
+            .enable_deviations(Deviations::Standard)
+            .build();
+        let illustrious_18 = RulesBuilder::new()
+            .enable_deviations(Deviations::Illustrious18)
+            .build();
+
+        for rules in [&none, &standard] {
+            assert_eq!(
+                hard_totals_chart(&vec![10, 2], 5, 0, -3, rules),
+                Some(Decision::Stand)
+            );
+        }
+
+        assert_eq!(
+            hard_totals_chart(&vec![10, 2], 5, 0, -3, &illustrious_18),
+            Some(Decision::Hit)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![10, 3], 3, 0, -3, &illustrious_18),
+            Some(Decision::Hit)
+        );
+        // the stand is an index play, basic strategy hits 12 against a 2
+        assert_eq!(
+            hard_totals_chart(&vec![10, 2], 2, 0, 3, &none),
+            Some(Decision::Hit)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![10, 2], 2, 0, 3, &illustrious_18),
+            Some(Decision::Stand)
+        );
+    }
+
+    #[test]
+    fn test_hard_single_and_double_deck() {
+        let single = RulesBuilder::new().decks(1).build();
+        let double = RulesBuilder::new().decks(2).build();
+        let shoe = RulesBuilder::new().decks(6).build();
+
+        assert_eq!(
+            hard_totals_chart(&vec![5, 4], 2, 0, 0, &single),
+            Some(Decision::Double)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![5, 4], 2, 0, 0, &double),
+            Some(Decision::Double)
+        );
+        assert_eq!(
+            hard_totals_chart(&vec![5, 4], 2, 0, 0, &shoe),
+            Some(Decision::Hit)
+        );
+
+        for duc in [5, 6] {
+            assert_eq!(
+                hard_totals_chart(&vec![5, 3], duc, 0, 0, &single),
+                Some(Decision::Double)
+            );
+            assert_eq!(
+                hard_totals_chart(&vec![6, 2], duc, 0, 0, &single),
+                Some(Decision::Hit)
+            );
+            assert_eq!(
+                hard_totals_chart(&vec![5, 3], duc, 0, 0, &double),
+                Some(Decision::Hit)
+            );
+        }
+
+        // 11 v A doubles regardless of the count with few decks
+        let single_s17 = RulesBuilder::new()
+            .decks(1)
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Standard)
+            .build();
+        assert_eq!(
+            hard_totals_chart(&vec![6, 5], 11, -5, -2, &single_s17),
+            Some(Decision::Double)
+        );
+    }
 }
@@ -0,0 +1,880 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compute_hand,
+    counter::{TrueCount, TrueCountRounding},
+    types::{CountingSystemType, GameType, Rules},
+};
+
+/// Hand an index play is for. Pairs are matched before the hard total they add up to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Hand {
+    Hard(u8),
+    Soft(u8),
+    /// Pair of the card, 11 for aces
+    Pair(u8),
+    Insurance,
+}
+
+/// Play made instead of basic strategy once the count reaches the index
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+    /// Play the hand out where basic strategy surrenders
+    NoSurrender,
+    Insure,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Count {
+    Running,
+    True,
+}
+
+/// How the count is compared to the index
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    AtLeast,
+    AtMost,
+    Above,
+    Below,
+    /// Whatever the count, for rule dependent plays the sets include such as surrendering 17
+    /// against an ace in H17 games
+    Always,
+}
+
+impl Comparison {
+    pub fn holds(&self, count: f32, index: f32) -> bool {
+        match self {
+            Comparison::AtLeast => count >= index,
+            Comparison::AtMost => count <= index,
+            Comparison::Above => count > index,
+            Comparison::Below => count < index,
+            Comparison::Always => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexPlay {
+    pub hand: Hand,
+    pub dealer_up_card: u8,
+    pub action: Action,
+    pub count: Count,
+    pub comparison: Comparison,
+    /// Hi-Lo index, true count indices are converted to the rules' counting system unless
+    /// `counting_system` is set
+    pub index: isize,
+    /// Only in H17 or S17 games
+    #[serde(default)]
+    pub game_type: Option<GameType>,
+    /// Only with at least this many decks
+    #[serde(default = "one_deck")]
+    pub min_decks: u8,
+    /// Convention the index was computed for, fractional true counts are rounded with it. Without
+    /// one the exact true count is compared to the index.
+    #[serde(default)]
+    pub rounding: Option<TrueCountRounding>,
+    /// System the index was computed for, it is used as is and only with that system
+    #[serde(default)]
+    pub counting_system: Option<CountingSystemType>,
+}
+
+fn one_deck() -> u8 {
+    1
+}
+
+impl IndexPlay {
+    pub const fn new(
+        hand: Hand,
+        dealer_up_card: u8,
+        action: Action,
+        count: Count,
+        comparison: Comparison,
+        index: isize,
+    ) -> Self {
+        Self {
+            hand,
+            dealer_up_card,
+            action,
+            count,
+            comparison,
+            index,
+            game_type: None,
+            min_decks: 1,
+            rounding: None,
+            counting_system: None,
+        }
+    }
+
+    pub const fn game_type(mut self, val: GameType) -> Self {
+        self.game_type = Some(val);
+        self
+    }
+
+    pub const fn min_decks(mut self, val: u8) -> Self {
+        self.min_decks = val;
+        self
+    }
+
+    pub const fn rounding(mut self, val: TrueCountRounding) -> Self {
+        self.rounding = Some(val);
+        self
+    }
+
+    pub const fn counting_system(mut self, val: CountingSystemType) -> Self {
+        self.counting_system = Some(val);
+        self
+    }
+
+    /// Whether the hand can be dealt and the action can be taken on it
+    pub fn is_valid(&self) -> bool {
+        let not_a_pair_play = !matches!(self.action, Action::Split | Action::Insure);
+        let hand = match self.hand {
+            Hand::Hard(total) => (4..=21).contains(&total) && not_a_pair_play,
+            Hand::Soft(total) => (12..=21).contains(&total) && not_a_pair_play,
+            Hand::Pair(card) => (2..=11).contains(&card) && self.action != Action::Insure,
+            Hand::Insurance => self.action == Action::Insure && self.dealer_up_card == 11,
+        };
+
+        hand && (2..=11).contains(&self.dealer_up_card)
+    }
+
+    /// Whether the play is for the game and the cards, the count is not looked at
+    pub fn applies_to(&self, cards: &[u8], dealer_up_card: u8, rules: &Rules) -> bool {
+        self.dealer_up_card == dealer_up_card
+            && self
+                .game_type
+                .as_ref()
+                .is_none_or(|game_type| game_type == rules.game_type())
+            && rules.decks() >= self.min_decks
+            && self
+                .counting_system
+                .is_none_or(|counting_system| counting_system == rules.counting_system())
+            && self.hand.matches(cards)
+    }
+
+    /// Whether the count calls for the play, counts as returned by `Rules::effective_exact_counts`
+    pub fn is_due(&self, running_count: isize, true_count: TrueCount, rules: &Rules) -> bool {
+        match self.count {
+            Count::Running => self
+                .comparison
+                .holds(running_count as f32, self.index as f32),
+            Count::True => {
+                let true_count = match self.rounding {
+                    Some(rounding) => true_count.rounded(rounding) as f32,
+                    None => true_count.value(),
+                };
+
+                self.comparison
+                    .holds(true_count, self.system_index(rules) as f32)
+            }
+        }
+    }
+
+    fn system_index(&self, rules: &Rules) -> isize {
+        match self.counting_system {
+            Some(_) => self.index,
+            None => rules.index(self.index),
+        }
+    }
+}
+
+impl Hand {
+    fn matches(&self, cards: &[u8]) -> bool {
+        match self {
+            Hand::Pair(card) => cards.len() == 2 && cards[0] == *card && cards[1] == *card,
+            Hand::Hard(total) | Hand::Soft(total) => {
+                let mut tmp = cards.to_vec();
+                let (hand_total, valid) = compute_hand(&mut tmp);
+                let soft = tmp.contains(&11);
+
+                valid && hand_total == *total && soft == matches!(self, Hand::Soft(_))
+            }
+            Hand::Insurance => cards.is_empty(),
+        }
+    }
+}
+
+/// First index play taking one of `actions` that applies to the hand and that the count calls for.
+/// The rules' custom plays replace the plays of their deviation set with the same hand, dealer up
+/// card and action, pair plays come before plays for the hand's total.
+pub fn deviation(
+    cards: &[u8],
+    dealer_up_card: u8,
+    running_count: isize,
+    true_count: impl Into<TrueCount>,
+    rules: &Rules,
+    actions: &[Action],
+) -> Option<Action> {
+    let true_count = true_count.into();
+    let applies = |play: &&IndexPlay| {
+        actions.contains(&play.action) && play.applies_to(cards, dealer_up_card, rules)
+    };
+
+    let custom: Vec<&IndexPlay> = rules.custom_index_plays().iter().filter(applies).collect();
+    let built_in = rules
+        .deviations()
+        .index_plays()
+        .iter()
+        .filter(applies)
+        .filter(|play| {
+            !custom.iter().any(|custom| {
+                custom.hand == play.hand
+                    && custom.dealer_up_card == play.dealer_up_card
+                    && custom.action == play.action
+            })
+        });
+    let plays: Vec<&IndexPlay> = custom.iter().copied().chain(built_in).collect();
+
+    let pairs = plays
+        .iter()
+        .filter(|play| matches!(play.hand, Hand::Pair(_)));
+    let others = plays
+        .iter()
+        .filter(|play| !matches!(play.hand, Hand::Pair(_)));
+
+    pairs
+        .chain(others)
+        .find(|play| play.is_due(running_count, true_count, rules))
+        .map(|play| play.action)
+}
+
+const fn true_count(
+    hand: Hand,
+    dealer_up_card: u8,
+    action: Action,
+    comparison: Comparison,
+    index: isize,
+) -> IndexPlay {
+    IndexPlay::new(hand, dealer_up_card, action, Count::True, comparison, index)
+}
+
+const fn running_count(
+    hand: Hand,
+    dealer_up_card: u8,
+    action: Action,
+    comparison: Comparison,
+    index: isize,
+) -> IndexPlay {
+    IndexPlay::new(
+        hand,
+        dealer_up_card,
+        action,
+        Count::Running,
+        comparison,
+        index,
+    )
+}
+
+const fn always(hand: Hand, dealer_up_card: u8, action: Action) -> IndexPlay {
+    IndexPlay::new(
+        hand,
+        dealer_up_card,
+        action,
+        Count::True,
+        Comparison::Always,
+        0,
+    )
+}
+
+use Action::*;
+use Comparison::{AtLeast, AtMost, Below};
+use Hand::{Hard, Insurance, Pair, Soft};
+
+pub const INSURANCE: IndexPlay = true_count(Insurance, 11, Insure, AtLeast, 3);
+
+pub const STAND_16_V_8: IndexPlay = true_count(Hard(16), 8, Stand, AtLeast, 7);
+pub const STAND_16_V_9: IndexPlay = true_count(Hard(16), 9, Stand, AtLeast, 4);
+/// Published Hi-Lo index of `STAND_16_V_9`
+pub const STAND_16_V_9_AT_5: IndexPlay = true_count(Hard(16), 9, Stand, AtLeast, 5);
+/// Any positive running count
+pub const STAND_16_V_10: IndexPlay = running_count(Hard(16), 10, Stand, Comparison::Above, 0);
+pub const STAND_16_V_A_H17: IndexPlay =
+    true_count(Hard(16), 11, Stand, AtLeast, 3).game_type(GameType::Hit17);
+pub const STAND_15_V_10: IndexPlay = true_count(Hard(15), 10, Stand, AtLeast, 4);
+pub const STAND_15_V_A_H17: IndexPlay =
+    true_count(Hard(15), 11, Stand, AtLeast, 5).game_type(GameType::Hit17);
+pub const HIT_13_V_2: IndexPlay = true_count(Hard(13), 2, Hit, AtMost, -1);
+pub const HIT_13_V_3: IndexPlay = true_count(Hard(13), 3, Hit, Below, -2);
+pub const HIT_13_V_4: IndexPlay = true_count(Hard(13), 4, Hit, Below, -4);
+pub const HIT_13_V_5: IndexPlay = true_count(Hard(13), 5, Hit, Below, -5);
+pub const HIT_13_V_6: IndexPlay = true_count(Hard(13), 6, Hit, Below, -4);
+pub const STAND_12_V_2: IndexPlay = true_count(Hard(12), 2, Stand, AtLeast, 3);
+pub const STAND_12_V_3: IndexPlay = true_count(Hard(12), 3, Stand, AtLeast, 2);
+/// Any negative running count
+pub const HIT_12_V_4: IndexPlay = running_count(Hard(12), 4, Hit, Below, 0);
+pub const HIT_12_V_5: IndexPlay = true_count(Hard(12), 5, Hit, Below, -2);
+pub const HIT_12_V_6: IndexPlay = true_count(Hard(12), 6, Hit, Below, -1);
+pub const HIT_11_V_10: IndexPlay = true_count(Hard(11), 10, Hit, Below, -4);
+pub const HIT_11_V_A_H17: IndexPlay =
+    true_count(Hard(11), 11, Hit, Below, -1).game_type(GameType::Hit17);
+/// `HIT_11_V_A_H17` is the H17 index
+pub const HIT_11_V_A_S17: IndexPlay = true_count(Hard(11), 11, Hit, Below, 1)
+    .game_type(GameType::Stand17)
+    .min_decks(3);
+pub const DOUBLE_10_V_10: IndexPlay = true_count(Hard(10), 10, Double, AtLeast, 4);
+pub const DOUBLE_10_V_A_H17: IndexPlay =
+    true_count(Hard(10), 11, Double, AtLeast, 3).game_type(GameType::Hit17);
+pub const DOUBLE_10_V_A_S17: IndexPlay =
+    true_count(Hard(10), 11, Double, AtLeast, 4).game_type(GameType::Stand17);
+pub const HIT_10_V_9: IndexPlay = true_count(Hard(10), 9, Hit, Below, -2);
+pub const DOUBLE_9_V_2: IndexPlay = true_count(Hard(9), 2, Double, AtLeast, 1);
+pub const HIT_9_V_3: IndexPlay = true_count(Hard(9), 3, Hit, Below, -1);
+pub const DOUBLE_9_V_7: IndexPlay = true_count(Hard(9), 7, Double, AtLeast, 3);
+pub const DOUBLE_8_V_5: IndexPlay = true_count(Hard(8), 5, Double, AtLeast, 4);
+pub const DOUBLE_8_V_6: IndexPlay = true_count(Hard(8), 6, Double, AtLeast, 2);
+
+/// Any negative running count
+pub const STAND_A8_V_6_H17: IndexPlay =
+    running_count(Soft(19), 6, Stand, Below, 0).game_type(GameType::Hit17);
+/// Single deck doubles A8 against a 6 at any count
+pub const STAND_A8_V_6_S17: IndexPlay = true_count(Soft(19), 6, Stand, Below, 1)
+    .game_type(GameType::Stand17)
+    .min_decks(2);
+pub const DOUBLE_A8_V_4: IndexPlay = true_count(Soft(19), 4, Double, AtLeast, 3);
+pub const DOUBLE_A8_V_5: IndexPlay = true_count(Soft(19), 5, Double, AtLeast, 1);
+/// H17 doubles A7 against a 2 at any count
+pub const DOUBLE_A7_V_2_S17: IndexPlay =
+    true_count(Soft(18), 2, Double, AtLeast, 1).game_type(GameType::Stand17);
+pub const DOUBLE_A6_V_2: IndexPlay = true_count(Soft(17), 2, Double, AtLeast, 1);
+pub const HIT_A6_V_3: IndexPlay = true_count(Soft(17), 3, Hit, Below, -1);
+pub const HIT_A5_V_4: IndexPlay = true_count(Soft(16), 4, Hit, Below, -1);
+pub const HIT_A4_V_4: IndexPlay = true_count(Soft(15), 4, Hit, Below, 0);
+pub const DOUBLE_A3_V_4: IndexPlay = true_count(Soft(14), 4, Double, AtLeast, 1);
+pub const DOUBLE_A2_V_4: IndexPlay = true_count(Soft(13), 4, Double, AtLeast, 3);
+
+pub const SPLIT_10S_V_4: IndexPlay = true_count(Pair(10), 4, Split, AtLeast, 6);
+pub const SPLIT_10S_V_5: IndexPlay = true_count(Pair(10), 5, Split, AtLeast, 5);
+pub const SPLIT_10S_V_6: IndexPlay = true_count(Pair(10), 6, Split, AtLeast, 4);
+
+pub const SURRENDER_17_V_A_H17: IndexPlay =
+    always(Hard(17), 11, Surrender).game_type(GameType::Hit17);
+pub const SURRENDER_8S_V_10: IndexPlay = true_count(Pair(8), 10, Surrender, AtLeast, 1);
+pub const SURRENDER_8S_V_A_H17: IndexPlay =
+    always(Pair(8), 11, Surrender).game_type(GameType::Hit17);
+pub const SURRENDER_16_V_8: IndexPlay = true_count(Hard(16), 8, Surrender, AtLeast, 4);
+pub const NO_SURRENDER_16_V_9: IndexPlay = true_count(Hard(16), 9, NoSurrender, AtMost, -1);
+/// Any negative running count
+pub const NO_SURRENDER_15_V_10: IndexPlay = running_count(Hard(15), 10, NoSurrender, Below, 0);
+/// Fab 4 form of `NO_SURRENDER_15_V_10`
+pub const NO_SURRENDER_15_V_10_BELOW_0: IndexPlay = true_count(Hard(15), 10, NoSurrender, Below, 0);
+pub const SURRENDER_15_V_9: IndexPlay = true_count(Hard(15), 9, Surrender, AtLeast, 2);
+pub const SURRENDER_15_V_A_H17: IndexPlay =
+    true_count(Hard(15), 11, Surrender, AtLeast, -1).game_type(GameType::Hit17);
+pub const SURRENDER_15_V_A_S17: IndexPlay =
+    true_count(Hard(15), 11, Surrender, AtLeast, 2).game_type(GameType::Stand17);
+/// Published Hi-Lo index of `SURRENDER_15_V_A_S17`
+pub const SURRENDER_15_V_A_S17_AT_1: IndexPlay =
+    true_count(Hard(15), 11, Surrender, AtLeast, 1).game_type(GameType::Stand17);
+pub const SURRENDER_14_V_9_H17: IndexPlay =
+    true_count(Hard(14), 9, Surrender, AtLeast, 6).game_type(GameType::Hit17);
+pub const SURRENDER_14_V_9_S17: IndexPlay =
+    true_count(Hard(14), 9, Surrender, AtLeast, 7).game_type(GameType::Stand17);
+pub const SURRENDER_14_V_10: IndexPlay = true_count(Hard(14), 10, Surrender, AtLeast, 4);
+/// Fab 4 index of `SURRENDER_14_V_10`
+pub const SURRENDER_14_V_10_AT_3: IndexPlay = true_count(Hard(14), 10, Surrender, AtLeast, 3);
+pub const SURRENDER_14_V_A_H17: IndexPlay =
+    true_count(Hard(14), 11, Surrender, AtLeast, 4).game_type(GameType::Hit17);
+pub const SURRENDER_14_V_A_S17: IndexPlay =
+    true_count(Hard(14), 11, Surrender, AtLeast, 6).game_type(GameType::Stand17);
+
+/// `Deviations::Standard`
+pub const STANDARD: &[IndexPlay] = &[
+    INSURANCE,
+    STAND_16_V_9,
+    STAND_16_V_10,
+    STAND_16_V_A_H17,
+    STAND_15_V_10,
+    STAND_15_V_A_H17,
+    HIT_13_V_2,
+    HIT_12_V_4,
+    STAND_12_V_2,
+    STAND_12_V_3,
+    HIT_11_V_A_S17,
+    DOUBLE_10_V_10,
+    DOUBLE_10_V_A_H17,
+    DOUBLE_10_V_A_S17,
+    DOUBLE_9_V_2,
+    DOUBLE_9_V_7,
+    DOUBLE_8_V_6,
+    STAND_A8_V_6_H17,
+    STAND_A8_V_6_S17,
+    DOUBLE_A8_V_4,
+    DOUBLE_A8_V_5,
+    DOUBLE_A6_V_2,
+    SPLIT_10S_V_4,
+    SPLIT_10S_V_5,
+    SPLIT_10S_V_6,
+    SURRENDER_17_V_A_H17,
+    SURRENDER_8S_V_10,
+    SURRENDER_8S_V_A_H17,
+    SURRENDER_16_V_8,
+    NO_SURRENDER_16_V_9,
+    NO_SURRENDER_15_V_10,
+    SURRENDER_15_V_9,
+    SURRENDER_15_V_A_H17,
+    SURRENDER_15_V_A_S17,
+];
+
+/// `Deviations::Extended1` to `Extended4`, `STANDARD` with doubling 8 against a 5 and
+/// surrendering 14
+pub const EXTENDED: &[IndexPlay] = &[
+    INSURANCE,
+    STAND_16_V_9,
+    STAND_16_V_10,
+    STAND_16_V_A_H17,
+    STAND_15_V_10,
+    STAND_15_V_A_H17,
+    HIT_13_V_2,
+    HIT_12_V_4,
+    STAND_12_V_2,
+    STAND_12_V_3,
+    HIT_11_V_A_S17,
+    DOUBLE_10_V_10,
+    DOUBLE_10_V_A_H17,
+    DOUBLE_10_V_A_S17,
+    DOUBLE_9_V_2,
+    DOUBLE_9_V_7,
+    DOUBLE_8_V_5,
+    DOUBLE_8_V_6,
+    STAND_A8_V_6_H17,
+    STAND_A8_V_6_S17,
+    DOUBLE_A8_V_4,
+    DOUBLE_A8_V_5,
+    DOUBLE_A6_V_2,
+    SPLIT_10S_V_4,
+    SPLIT_10S_V_5,
+    SPLIT_10S_V_6,
+    SURRENDER_17_V_A_H17,
+    SURRENDER_8S_V_10,
+    SURRENDER_8S_V_A_H17,
+    SURRENDER_16_V_8,
+    NO_SURRENDER_16_V_9,
+    NO_SURRENDER_15_V_10,
+    SURRENDER_15_V_9,
+    SURRENDER_15_V_A_H17,
+    SURRENDER_15_V_A_S17,
+    SURRENDER_14_V_9_H17,
+    SURRENDER_14_V_9_S17,
+    SURRENDER_14_V_10,
+    SURRENDER_14_V_A_H17,
+    SURRENDER_14_V_A_S17,
+];
+
+/// The 18 plays worth the most to a Hi-Lo counter, 10 against an ace has an H17 and an S17 index
+pub const ILLUSTRIOUS_18: &[IndexPlay] = &[
+    INSURANCE,
+    STAND_16_V_10,
+    STAND_15_V_10,
+    SPLIT_10S_V_5,
+    SPLIT_10S_V_6,
+    DOUBLE_10_V_10,
+    STAND_12_V_3,
+    STAND_12_V_2,
+    HIT_11_V_A_S17,
+    DOUBLE_9_V_2,
+    DOUBLE_10_V_A_H17,
+    DOUBLE_10_V_A_S17,
+    DOUBLE_9_V_7,
+    STAND_16_V_9_AT_5,
+    HIT_13_V_2,
+    HIT_12_V_4,
+    HIT_12_V_5,
+    HIT_12_V_6,
+    HIT_13_V_3,
+];
+
+/// The four surrender plays worth the most, 15 against an ace has an H17 and an S17 index
+pub const FAB_4: &[IndexPlay] = &[
+    SURRENDER_14_V_10_AT_3,
+    NO_SURRENDER_15_V_10_BELOW_0,
+    SURRENDER_15_V_9,
+    SURRENDER_15_V_A_H17,
+    SURRENDER_15_V_A_S17_AT_1,
+];
+
+pub const ILLUSTRIOUS_18_AND_FAB_4: &[IndexPlay] = &[
+    INSURANCE,
+    STAND_16_V_10,
+    STAND_15_V_10,
+    SPLIT_10S_V_5,
+    SPLIT_10S_V_6,
+    DOUBLE_10_V_10,
+    STAND_12_V_3,
+    STAND_12_V_2,
+    HIT_11_V_A_S17,
+    DOUBLE_9_V_2,
+    DOUBLE_10_V_A_H17,
+    DOUBLE_10_V_A_S17,
+    DOUBLE_9_V_7,
+    STAND_16_V_9_AT_5,
+    HIT_13_V_2,
+    HIT_12_V_4,
+    HIT_12_V_5,
+    HIT_12_V_6,
+    HIT_13_V_3,
+    SURRENDER_14_V_10_AT_3,
+    NO_SURRENDER_15_V_10_BELOW_0,
+    SURRENDER_15_V_9,
+    SURRENDER_15_V_A_H17,
+    SURRENDER_15_V_A_S17_AT_1,
+];
+
+/// Full Hi-Lo index set for shoe games, the H17 or S17 index of a play is used according to the
+/// game
+pub const FULL: &[IndexPlay] = &[
+    INSURANCE,
+    STAND_16_V_8,
+    STAND_16_V_9_AT_5,
+    STAND_16_V_10,
+    STAND_16_V_A_H17,
+    STAND_15_V_10,
+    STAND_15_V_A_H17,
+    HIT_13_V_2,
+    HIT_13_V_3,
+    HIT_13_V_4,
+    HIT_13_V_5,
+    HIT_13_V_6,
+    STAND_12_V_2,
+    STAND_12_V_3,
+    HIT_12_V_4,
+    HIT_12_V_5,
+    HIT_12_V_6,
+    HIT_11_V_10,
+    HIT_11_V_A_H17,
+    HIT_11_V_A_S17,
+    DOUBLE_10_V_10,
+    DOUBLE_10_V_A_H17,
+    DOUBLE_10_V_A_S17,
+    HIT_10_V_9,
+    DOUBLE_9_V_2,
+    HIT_9_V_3,
+    DOUBLE_9_V_7,
+    DOUBLE_8_V_5,
+    DOUBLE_8_V_6,
+    STAND_A8_V_6_H17,
+    STAND_A8_V_6_S17,
+    DOUBLE_A8_V_4,
+    DOUBLE_A8_V_5,
+    DOUBLE_A7_V_2_S17,
+    DOUBLE_A6_V_2,
+    HIT_A6_V_3,
+    HIT_A5_V_4,
+    HIT_A4_V_4,
+    DOUBLE_A3_V_4,
+    DOUBLE_A2_V_4,
+    SPLIT_10S_V_4,
+    SPLIT_10S_V_5,
+    SPLIT_10S_V_6,
+    SURRENDER_17_V_A_H17,
+    SURRENDER_8S_V_10,
+    SURRENDER_8S_V_A_H17,
+    SURRENDER_16_V_8,
+    NO_SURRENDER_16_V_9,
+    NO_SURRENDER_15_V_10_BELOW_0,
+    SURRENDER_15_V_9,
+    SURRENDER_15_V_A_H17,
+    SURRENDER_15_V_A_S17_AT_1,
+    SURRENDER_14_V_9_H17,
+    SURRENDER_14_V_9_S17,
+    SURRENDER_14_V_10_AT_3,
+    SURRENDER_14_V_A_H17,
+    SURRENDER_14_V_A_S17,
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Deviations, RulesBuilder, RulesError};
+
+    use super::*;
+
+    fn rules_with(deviations: Deviations) -> Rules {
+        RulesBuilder::new()
+            .surrender(true)
+            .enable_deviations(deviations)
+            .build()
+    }
+
+    #[test]
+    fn named_sets() {
+        // 18 plays, 10 against an ace counted once
+        assert_eq!(ILLUSTRIOUS_18.len(), 19);
+        assert_eq!(FAB_4.len(), 5);
+
+        for play in ILLUSTRIOUS_18.iter().chain(FAB_4) {
+            assert!(FULL.contains(play), "{play:?} missing from the full set");
+        }
+
+        assert_eq!(
+            ILLUSTRIOUS_18_AND_FAB_4,
+            [ILLUSTRIOUS_18, FAB_4].concat().as_slice()
+        );
+
+        for play in STANDARD {
+            assert!(
+                EXTENDED.contains(play),
+                "{play:?} missing from the extended set"
+            );
+        }
+    }
+
+    #[test]
+    fn plays_are_unique_per_game() {
+        for set in [
+            STANDARD,
+            EXTENDED,
+            ILLUSTRIOUS_18,
+            FAB_4,
+            ILLUSTRIOUS_18_AND_FAB_4,
+            FULL,
+        ] {
+            for (i, play) in set.iter().enumerate() {
+                assert!(!set[i + 1..].iter().any(|other| {
+                    other.hand == play.hand
+                        && other.dealer_up_card == play.dealer_up_card
+                        && other.action == play.action
+                        && other.game_type == play.game_type
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn looks_up_the_due_play() {
+        let rules = rules_with(Deviations::Illustrious18);
+
+        assert_eq!(
+            deviation(&[10, 2], 5, 0, -3, &rules, &[Hit, Stand, Double]),
+            Some(Hit)
+        );
+        assert_eq!(
+            deviation(&[10, 2], 5, 0, -2, &rules, &[Hit, Stand, Double]),
+            None
+        );
+        // surrender plays aren't part of the Illustrious 18
+        assert_eq!(
+            deviation(&[10, 5], 9, 0, 5, &rules, &[Surrender, NoSurrender]),
+            None
+        );
+
+        let rules = rules_with(Deviations::Fab4);
+        assert_eq!(
+            deviation(&[10, 4], 10, 0, 3, &rules, &[Surrender, NoSurrender]),
+            Some(Surrender)
+        );
+        assert_eq!(deviation(&[10, 2], 5, 0, -3, &rules, &[Hit]), None);
+    }
+
+    #[test]
+    fn published_indices() {
+        let actions = [Hit, Stand, Double];
+
+        let standard = rules_with(Deviations::Standard);
+        assert_eq!(
+            deviation(&[10, 6], 9, 0, 4, &standard, &actions),
+            Some(Stand)
+        );
+
+        let illustrious_18 = rules_with(Deviations::Illustrious18);
+        assert_eq!(
+            deviation(&[10, 6], 9, 0, 4, &illustrious_18, &actions),
+            None
+        );
+        assert_eq!(
+            deviation(&[10, 6], 9, 0, 5, &illustrious_18, &actions),
+            Some(Stand)
+        );
+
+        let fab_4 = RulesBuilder::new()
+            .surrender(true)
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Fab4)
+            .build();
+        assert_eq!(
+            deviation(&[10, 5], 11, 0, 1, &fab_4, &[Surrender]),
+            Some(Surrender)
+        );
+        assert_eq!(deviation(&[10, 5], 11, 0, 0, &fab_4, &[Surrender]), None);
+    }
+
+    #[test]
+    fn full_set() {
+        let actions = [Hit, Stand, Double];
+        let h17 = rules_with(Deviations::Full);
+        let s17 = RulesBuilder::new()
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Full)
+            .build();
+
+        assert_eq!(deviation(&[11, 2], 4, 0, 3, &h17, &actions), Some(Double));
+        assert_eq!(deviation(&[11, 2], 4, 0, 2, &h17, &actions), None);
+        assert_eq!(deviation(&[11, 4], 4, 0, -1, &h17, &actions), Some(Hit));
+        assert_eq!(deviation(&[4, 5], 3, 0, -2, &h17, &actions), Some(Hit));
+        assert_eq!(deviation(&[4, 5], 3, 0, -1, &h17, &actions), None);
+        assert_eq!(deviation(&[4, 6], 9, 0, -3, &h17, &actions), Some(Hit));
+        assert_eq!(deviation(&[10, 6], 8, 0, 7, &h17, &actions), Some(Stand));
+        assert_eq!(deviation(&[10, 3], 5, 0, -6, &h17, &actions), Some(Hit));
+
+        // 11 against an ace has an H17 and an S17 index
+        assert_eq!(deviation(&[5, 6], 11, 0, -2, &h17, &actions), Some(Hit));
+        assert_eq!(deviation(&[5, 6], 11, 0, -1, &h17, &actions), None);
+        assert_eq!(deviation(&[5, 6], 11, 0, 0, &s17, &actions), Some(Hit));
+
+        // A7 against a 2 is only an index play in S17
+        assert_eq!(deviation(&[11, 7], 2, 0, 1, &s17, &actions), Some(Double));
+        assert_eq!(deviation(&[11, 7], 2, 0, 1, &h17, &actions), None);
+    }
+
+    #[test]
+    fn hands() {
+        assert!(Hard(16).matches(&[10, 6]));
+        assert!(Hard(16).matches(&[8, 8]));
+        assert!(!Hard(16).matches(&[11, 5]));
+        assert!(Soft(16).matches(&[11, 5]));
+        assert!(Hard(16).matches(&[11, 5, 10]));
+        assert!(Pair(8).matches(&[8, 8]));
+        assert!(!Pair(8).matches(&[8, 8, 2]));
+        assert!(Insurance.matches(&[]));
+    }
+
+    #[test]
+    fn selected_plays() {
+        let rules = RulesBuilder::new()
+            .custom_index_plays(vec![STAND_16_V_10, INSURANCE])
+            .build();
+
+        assert_eq!(deviation(&[10, 6], 10, 1, 0, &rules, &[Stand]), Some(Stand));
+        assert_eq!(deviation(&[10, 5], 10, 0, 4, &rules, &[Stand]), None);
+        assert_eq!(deviation(&[], 11, 0, 3, &rules, &[Insure]), Some(Insure));
+    }
+
+    #[test]
+    fn custom_plays_replace_built_in_ones() {
+        let plays: Vec<IndexPlay> = serde_json::from_str(
+            r#"[
+                {
+                    "hand": { "Hard": 16 },
+                    "dealer_up_card": 10,
+                    "action": "Stand",
+                    "count": "True",
+                    "comparison": "AtLeast",
+                    "index": 2
+                },
+                {
+                    "hand": { "Hard": 13 },
+                    "dealer_up_card": 6,
+                    "action": "Hit",
+                    "count": "Running",
+                    "comparison": "AtMost",
+                    "index": -10
+                }
+            ]"#,
+        )
+        .unwrap();
+        let rules = RulesBuilder::new()
+            .enable_deviations(Deviations::Standard)
+            .custom_index_plays(plays)
+            .build();
+        let actions = [Hit, Stand, Double];
+
+        // the built-in play stands on any positive running count
+        assert_eq!(deviation(&[10, 6], 10, 5, 1, &rules, &actions), None);
+        assert_eq!(deviation(&[10, 6], 10, 5, 2, &rules, &actions), Some(Stand));
+        assert_eq!(deviation(&[10, 3], 6, -10, 0, &rules, &actions), Some(Hit));
+        assert_eq!(deviation(&[10, 3], 6, -9, -5, &rules, &actions), None);
+        // hands the custom plays don't cover keep the built-in plays
+        assert_eq!(deviation(&[10, 6], 9, 0, 4, &rules, &actions), Some(Stand));
+
+        // custom plays apply without a deviation set
+        let rules = RulesBuilder::new()
+            .custom_index_plays(vec![STAND_16_V_10])
+            .build();
+
+        assert!(rules.has_index_plays());
+        assert_eq!(deviation(&[10, 6], 10, 1, 0, &rules, &actions), Some(Stand));
+    }
+
+    #[test]
+    fn custom_plays_replace_the_same_action_only() {
+        let rules = RulesBuilder::new()
+            .surrender(true)
+            .enable_deviations(Deviations::Standard)
+            .custom_index_plays(vec![true_count(Hard(16), 10, Surrender, AtLeast, 2)])
+            .build();
+
+        // 8-8 keeps its pair play, only the surrender of a hard 16 is replaced
+        assert_eq!(
+            deviation(&[8, 8], 10, 0, 1, &rules, &[Surrender]),
+            Some(Surrender)
+        );
+        assert_eq!(deviation(&[10, 6], 10, 0, 1, &rules, &[Surrender]), None);
+        assert_eq!(
+            deviation(&[10, 6], 10, 0, 2, &rules, &[Surrender]),
+            Some(Surrender)
+        );
+        // other actions for the hand are kept
+        assert_eq!(deviation(&[10, 6], 10, 1, 0, &rules, &[Stand]), Some(Stand));
+    }
+
+    #[test]
+    fn native_indices_are_not_converted() {
+        let play =
+            true_count(Hard(16), 9, Stand, AtLeast, 5).counting_system(CountingSystemType::Zen);
+        let zen = RulesBuilder::new()
+            .counting_system(CountingSystemType::Zen)
+            .custom_index_plays(vec![play.clone()])
+            .build();
+        let hi_lo = RulesBuilder::new().custom_index_plays(vec![play]).build();
+
+        assert_ne!(zen.index(5), 5);
+        assert_eq!(deviation(&[10, 6], 9, 0, 5, &zen, &[Stand]), Some(Stand));
+        assert_eq!(deviation(&[10, 6], 9, 0, 4, &zen, &[Stand]), None);
+        // computed for another system
+        assert_eq!(deviation(&[10, 6], 9, 0, 10, &hi_lo, &[Stand]), None);
+    }
+
+    #[test]
+    fn invalid_plays() {
+        assert!(FULL.iter().all(IndexPlay::is_valid));
+
+        for play in [
+            true_count(Hard(16), 1, Stand, AtLeast, 0),
+            true_count(Hard(22), 10, Stand, AtLeast, 0),
+            true_count(Soft(11), 10, Stand, AtLeast, 0),
+            true_count(Hard(16), 10, Split, AtLeast, 0),
+            true_count(Insurance, 10, Insure, AtLeast, 3),
+        ] {
+            let errors = RulesBuilder::new()
+                .custom_index_plays(vec![play.clone()])
+                .validate();
+
+            assert_eq!(errors, vec![RulesError::InvalidIndexPlay(play)]);
+        }
+    }
+
+    #[test]
+    fn game_type_and_decks() {
+        let s17 = RulesBuilder::new()
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Full)
+            .build();
+        let double_deck_s17 = RulesBuilder::new()
+            .decks(2)
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Full)
+            .build();
+
+        assert_eq!(deviation(&[5, 6], 11, 0, 0, &s17, &[Hit]), Some(Hit));
+        assert_eq!(deviation(&[5, 6], 11, 0, 0, &double_deck_s17, &[Hit]), None);
+        assert_eq!(
+            deviation(&[10, 5], 11, 0, 1, &s17, &[Surrender]),
+            Some(Surrender)
+        );
+        assert_eq!(deviation(&[10, 5], 11, 0, 0, &s17, &[Surrender]), None);
+    }
+}
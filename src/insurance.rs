@@ -1,17 +1,33 @@
-use crate::types::Rules;
+use crate::{
+    counter::TrueCount,
+    index_plays::{Action, deviation},
+    types::Rules,
+};
 
 // * Returned decision should be used for both insurance and even money
-pub fn should_i_take_insurance(dealer_up_card: u8, true_count: isize, rules: &Rules) -> bool {
-    if rules.enable_deviations() == 0 {
-        return false;
-    }
+pub fn should_i_take_insurance(
+    dealer_up_card: u8,
+    running_count: isize,
+    true_count: impl Into<TrueCount>,
+    rules: &Rules,
+) -> bool {
+    let (running_count, true_count) =
+        rules.effective_exact_counts(running_count, true_count.into());
 
-    dealer_up_card == 11 && true_count >= 3
+    deviation(
+        &[],
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+        &[Action::Insure],
+    )
+    .is_some()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::types::Deviations;
+    use crate::types::{CountingSystemType, Deviations};
 
     use super::*;
 
@@ -20,9 +36,9 @@ mod tests {
         let mut rules = Rules::default();
         rules.set_enable_deviations(Deviations::Standard);
 
-        assert!(should_i_take_insurance(11, 3, &rules));
-        assert!(should_i_take_insurance(11, 5, &rules));
-        assert!(should_i_take_insurance(11, 4, &rules));
+        assert!(should_i_take_insurance(11, 0, 3, &rules));
+        assert!(should_i_take_insurance(11, 0, 5, &rules));
+        assert!(should_i_take_insurance(11, 0, 4, &rules));
     }
 
     #[test]
@@ -30,11 +46,11 @@ mod tests {
         let mut rules = Rules::default();
         rules.set_enable_deviations(Deviations::Standard);
 
-        assert!(!should_i_take_insurance(11, 1, &rules));
-        assert!(!should_i_take_insurance(11, 2, &rules));
-        assert!(!should_i_take_insurance(11, 1, &rules));
+        assert!(!should_i_take_insurance(11, 0, 1, &rules));
+        assert!(!should_i_take_insurance(11, 0, 2, &rules));
+        assert!(!should_i_take_insurance(11, 0, 1, &rules));
 
-        assert!(!should_i_take_insurance(10, 5, &rules));
+        assert!(!should_i_take_insurance(10, 0, 5, &rules));
     }
 
     #[test]
@@ -42,8 +58,19 @@ mod tests {
         let mut rules = Rules::default();
         rules.set_enable_deviations(Deviations::None);
 
-        assert!(!should_i_take_insurance(11, 3, &rules));
-        assert!(!should_i_take_insurance(11, 5, &rules));
-        assert!(!should_i_take_insurance(11, 4, &rules));
+        assert!(!should_i_take_insurance(11, 0, 3, &rules));
+        assert!(!should_i_take_insurance(11, 0, 5, &rules));
+        assert!(!should_i_take_insurance(11, 0, 4, &rules));
+    }
+
+    #[test]
+    fn unbalanced_counting_system_uses_running_count() {
+        let mut rules = Rules::default();
+        rules.set_enable_deviations(Deviations::Standard);
+        rules.set_counting_system(CountingSystemType::KnockOut);
+
+        // the true count is ignored, the running count at the pivot is worth +4
+        assert!(should_i_take_insurance(11, 4, 0, &rules));
+        assert!(!should_i_take_insurance(11, -20, 10, &rules));
     }
 }
@@ -0,0 +1,87 @@
+use crate::{
+    advantage::{advantage, variance},
+    types::Rules,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KellyBet {
+    /// Estimated player advantage as a fraction of the bet
+    pub advantage: f64,
+    /// Variance of a single hand
+    pub variance: f64,
+    pub full: f64,
+    pub half: f64,
+    pub quarter: f64,
+}
+
+/// Kelly bets for the bankroll at the true count. Bets are rounded down to a multiple of
+/// `table_unit` and are zero whenever the player is at a disadvantage.
+pub fn kelly_bet(rules: &Rules, true_count: isize, bankroll: f64, table_unit: f64) -> KellyBet {
+    let advantage = advantage(rules, true_count);
+    let variance = variance(rules);
+
+    let full = if advantage > 0.0 {
+        bankroll * advantage / variance
+    } else {
+        0.0
+    };
+
+    KellyBet {
+        advantage,
+        variance,
+        full: round_to_unit(full, table_unit),
+        half: round_to_unit(full / 2.0, table_unit),
+        quarter: round_to_unit(full / 4.0, table_unit),
+    }
+}
+
+fn round_to_unit(bet: f64, table_unit: f64) -> f64 {
+    if table_unit <= 0.0 {
+        return bet;
+    }
+
+    (bet / table_unit).floor() * table_unit
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::RulesBuilder;
+
+    use super::*;
+
+    #[test]
+    fn no_bet_without_an_advantage() {
+        let rules = RulesBuilder::new().build();
+        let bet = kelly_bet(&rules, 0, 10_000.0, 5.0);
+
+        assert!(bet.advantage < 0.0);
+        assert_eq!(bet.full, 0.0);
+        assert_eq!(bet.half, 0.0);
+        assert_eq!(bet.quarter, 0.0);
+    }
+
+    #[test]
+    fn bets_scale_with_bankroll_and_count() {
+        let rules = RulesBuilder::new().build();
+        let bet = kelly_bet(&rules, 4, 100_000.0, 5.0);
+
+        let expected = 100_000.0 * bet.advantage / bet.variance;
+
+        assert!(bet.full <= expected && expected - bet.full < 5.0);
+        assert_eq!(bet.full % 5.0, 0.0);
+        assert!(bet.half <= bet.full / 2.0);
+        assert!(bet.quarter <= bet.half / 2.0 + 5.0);
+
+        let higher = kelly_bet(&rules, 6, 100_000.0, 5.0);
+        assert!(higher.full > bet.full);
+    }
+
+    #[test]
+    fn unrounded_when_no_table_unit() {
+        let rules = RulesBuilder::new().build();
+        let bet = kelly_bet(&rules, 5, 10_000.0, 0.0);
+
+        assert_eq!(bet.full, 10_000.0 * bet.advantage / bet.variance);
+        assert_eq!(bet.quarter, bet.full / 4.0);
+    }
+}
@@ -1,9 +1,112 @@
+pub mod advantage;
+pub mod bet_ramp;
+pub mod counter;
+pub mod counting_drills;
+pub mod counting_system;
 pub mod hard_totals_chart;
+pub mod index_plays;
 pub mod insurance;
+pub mod kelly;
+pub mod payout;
+pub mod risk_of_ruin;
+pub mod rng;
+pub mod settlement;
+pub mod shoe;
+pub mod shorthand;
+pub mod simulation;
 pub mod soft_totals_chart;
+pub mod spaced_repetition;
 pub mod splits_chart;
+pub mod statistics;
 pub mod surrender;
+pub mod trainer;
 pub mod types;
+pub mod wasm;
+
+use counter::TrueCount;
+use hard_totals_chart::hard_totals_chart_without_split;
+use soft_totals_chart::soft_totals_chart;
+use splits_chart::should_i_split;
+use surrender::should_i_surrender;
+use types::{Decision, Rules};
+
+/// Best play for a starting hand, checks surrender, splits, soft totals and hard totals in that
+/// order
+pub fn get_decision(
+    cards_in_hand: &Vec<u8>,
+    dealer_up_card: u8,
+    running_count: isize,
+    true_count: impl Into<TrueCount>,
+    rules: &Rules,
+) -> Decision {
+    let true_count = true_count.into();
+
+    if should_i_surrender(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+    ) {
+        return Decision::Surrender;
+    }
+
+    if should_i_split(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+    ) {
+        return Decision::Split;
+    }
+
+    get_decision_without_split(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+    )
+}
+
+/// Same as `get_decision` for hands that can't be split or surrendered anymore, pairs are played
+/// by their total
+pub fn get_decision_without_split(
+    cards_in_hand: &Vec<u8>,
+    dealer_up_card: u8,
+    running_count: isize,
+    true_count: impl Into<TrueCount>,
+    rules: &Rules,
+) -> Decision {
+    let true_count = true_count.into();
+
+    let is_pair = cards_in_hand.len() == 2 && cards_in_hand[0] == cards_in_hand[1];
+
+    // soft 12
+    if is_pair && cards_in_hand[0] == 11 {
+        return Decision::Hit;
+    }
+
+    if let Some(decision) = soft_totals_chart(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+    ) {
+        return decision;
+    }
+
+    hard_totals_chart_without_split(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+    )
+    .unwrap_or(Decision::Stand)
+}
 
 pub fn compute_hand(cards: &mut Vec<u8>) -> (u8, bool) {
     // Check for blackjack first (natural 21)
@@ -57,8 +160,66 @@ pub fn compute_hand(cards: &mut Vec<u8>) -> (u8, bool) {
 
 #[cfg(test)]
 mod lib_tests {
+    use crate::types::{Deviations, RulesBuilder};
+
     use super::*;
 
+    #[test]
+    fn test_get_decision() {
+        let rules = Rules::default();
+
+        assert_eq!(
+            get_decision(&vec![10, 6], 10, 0, 0, &rules),
+            Decision::Surrender
+        );
+        assert_eq!(get_decision(&vec![8, 8], 6, 0, 0, &rules), Decision::Split);
+        assert_eq!(
+            get_decision(&vec![11, 7], 3, 0, 0, &rules),
+            Decision::Double
+        );
+        assert_eq!(get_decision(&vec![10, 2], 2, 0, 0, &rules), Decision::Hit);
+        assert_eq!(
+            get_decision(&vec![10, 10], 6, 0, 0, &rules),
+            Decision::Stand
+        );
+        assert_eq!(get_decision(&vec![9, 9], 7, 0, 0, &rules), Decision::Stand);
+
+        let rules = RulesBuilder::new()
+            .enable_deviations(Deviations::Standard)
+            .build();
+
+        assert_eq!(
+            get_decision(&vec![10, 10], 6, 0, 3.8, &rules),
+            Decision::Stand
+        );
+        assert_eq!(
+            get_decision(&vec![10, 10], 6, 0, 4.2, &rules),
+            Decision::Split
+        );
+    }
+
+    #[test]
+    fn test_get_decision_without_split() {
+        let rules = Rules::default();
+
+        assert_eq!(
+            get_decision_without_split(&vec![8, 8], 10, 0, 0, &rules),
+            Decision::Hit
+        );
+        assert_eq!(
+            get_decision_without_split(&vec![8, 8], 6, 0, 0, &rules),
+            Decision::Stand
+        );
+        assert_eq!(
+            get_decision_without_split(&vec![11, 11], 6, 0, 0, &rules),
+            Decision::Hit
+        );
+        assert_eq!(
+            get_decision_without_split(&vec![2, 2], 9, 0, 0, &rules),
+            Decision::Hit
+        );
+    }
+
     #[test]
     fn test_compute_hand() {
         let cards = &mut vec![11, 10];
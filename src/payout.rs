@@ -0,0 +1,233 @@
+use crate::{
+    compute_hand,
+    shoe::CARDS_PER_DECK,
+    types::{Bonuses, Ratio, Rules},
+};
+
+/// Chance a blackjack is suited. The ten has to match the ace's suit and a quarter of the tens in
+/// the shoe do, whatever the number of decks.
+pub const SUITED_BLACKJACK_PROBABILITY: f64 = 0.25;
+
+/// Units won on a blackjack
+pub fn blackjack(rules: &Rules, suited: bool) -> f64 {
+    let payout = rules.blackjack_payout();
+
+    if suited {
+        payout.suited_ratio().value()
+    } else {
+        payout.ratio().value()
+    }
+}
+
+/// `blackjack` averaged over suits, for callers that don't track them
+pub fn expected_blackjack(rules: &Rules) -> f64 {
+    SUITED_BLACKJACK_PROBABILITY * blackjack(rules, true)
+        + (1.0 - SUITED_BLACKJACK_PROBABILITY) * blackjack(rules, false)
+}
+
+/// Units won by a bonus hand instead of the normal settlement, `None` when the hand doesn't
+/// qualify. Only hands that weren't doubled should be passed.
+pub fn bonus(rules: &Rules, cards: &[u8], suited: bool) -> Option<f64> {
+    bonus_ratio(rules.bonuses(), cards, suited).map(|ratio| ratio.value())
+}
+
+/// `settlement` is what the hand gets without a bonus, returned with the hand's bonus averaged
+/// over suits for callers that don't track them
+pub fn with_expected_bonus(rules: &Rules, cards: &[u8], settlement: f64) -> f64 {
+    if !rules.bonuses().any() {
+        return settlement;
+    }
+
+    let suited_probability = suited_probability(cards, rules.decks());
+    let suited = bonus(rules, cards, true).unwrap_or(settlement);
+    let mixed = bonus(rules, cards, false).unwrap_or(settlement);
+
+    suited_probability * suited + (1.0 - suited_probability) * mixed
+}
+
+/// Chance that cards of the given ranks are all of one suit when dealt from `decks` decks
+pub fn suited_probability(cards: &[u8], decks: u8) -> f64 {
+    let decks = decks.max(1) as f64;
+    let mut dealt = [0.0; 12];
+    let mut probability = 1.0;
+
+    for (i, card) in cards.iter().enumerate() {
+        let rank = (*card).min(11) as usize;
+        let per_suit = if rank == 10 { 4.0 } else { 1.0 } * decks;
+
+        // every earlier card of the rank is of the first card's suit
+        if i > 0 {
+            probability *= (per_suit - dealt[rank]).max(0.0) / (4.0 * per_suit - dealt[rank]);
+        }
+
+        dealt[rank] += 1.0;
+    }
+
+    probability
+}
+
+/// Player advantage the bonuses add per hand. Every three card 21 dealt off the top of the shoe
+/// is counted as if the player drew to it and would otherwise have won even money, so it is an
+/// upper bound.
+pub fn bonus_value(rules: &Rules) -> f64 {
+    if !rules.bonuses().any() {
+        return 0.0;
+    }
+
+    let decks = rules.decks() as f64;
+    let total_cards = decks * CARDS_PER_DECK as f64;
+    let mut value = 0.0;
+
+    for first in 2..=11u8 {
+        for second in 2..=11u8 {
+            if first + second == 21 {
+                continue;
+            }
+
+            for third in 2..=11u8 {
+                let cards = [first, second, third];
+                let mut dealt = [0.0; 12];
+                let mut probability = 1.0;
+
+                for (i, card) in cards.iter().enumerate() {
+                    let rank = *card as usize;
+                    let in_shoe = if rank == 10 { 16.0 } else { 4.0 } * decks;
+
+                    probability *= (in_shoe - dealt[rank]).max(0.0) / (total_cards - i as f64);
+                    dealt[rank] += 1.0;
+                }
+
+                value += probability * (with_expected_bonus(rules, &cards, 1.0) - 1.0);
+            }
+        }
+    }
+
+    value
+}
+
+fn bonus_ratio(bonuses: &Bonuses, cards: &[u8], suited: bool) -> Option<Ratio> {
+    if cards.len() < 3 || compute_hand(&mut cards.to_vec()).0 != 21 {
+        return None;
+    }
+
+    let mut ranks = cards.to_vec();
+    ranks.sort_unstable();
+
+    let named = match ranks.as_slice() {
+        [6, 7, 8] => bonuses.six_seven_eight.ratio(suited),
+        [7, 7, 7] => bonuses.seven_seven_seven.ratio(suited),
+        _ => None,
+    };
+    let suited_21 = bonuses.suited_21.filter(|_| suited);
+
+    [named, suited_21]
+        .into_iter()
+        .flatten()
+        .max_by(|a, b| a.value().total_cmp(&b.value()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{BlackjackPayout, Bonus, RulesBuilder};
+
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn spanish_bonuses() -> Bonuses {
+        Bonuses {
+            six_seven_eight: Bonus {
+                mixed: Some(Ratio::new(3, 2)),
+                suited: Some(Ratio::new(2, 1)),
+            },
+            seven_seven_seven: Bonus {
+                mixed: Some(Ratio::new(3, 2)),
+                suited: Some(Ratio::new(2, 1)),
+            },
+            suited_21: None,
+        }
+    }
+
+    #[test]
+    fn blackjack_payouts() {
+        let rules = RulesBuilder::new()
+            .blackjack_payout(BlackjackPayout::SixToFiveSuitedTwoToOne)
+            .build();
+
+        assert_eq!(blackjack(&rules, false), 1.2);
+        assert_eq!(blackjack(&rules, true), 2.0);
+        assert!(approx(expected_blackjack(&rules), 1.4));
+
+        let rules = RulesBuilder::new()
+            .blackjack_payout(BlackjackPayout::Custom {
+                payout: Ratio::new(7, 5),
+                suited: Ratio::new(7, 5),
+            })
+            .build();
+
+        assert!(approx(expected_blackjack(&rules), 1.4));
+    }
+
+    #[test]
+    fn suited_probabilities() {
+        assert!(approx(suited_probability(&[11, 10], 6), 0.25));
+        assert!(approx(suited_probability(&[6, 7, 8], 2), 1.0 / 16.0));
+        // no two sevens of one suit in a single deck
+        assert_eq!(suited_probability(&[7, 7, 7], 1), 0.0);
+        assert!(approx(
+            suited_probability(&[7, 7, 7], 6),
+            5.0 / 23.0 * 4.0 / 22.0
+        ));
+    }
+
+    #[test]
+    fn bonus_hands() {
+        let rules = RulesBuilder::new().bonuses(spanish_bonuses()).build();
+
+        assert_eq!(bonus(&rules, &[8, 6, 7], false), Some(1.5));
+        assert_eq!(bonus(&rules, &[7, 8, 6], true), Some(2.0));
+        assert_eq!(bonus(&rules, &[7, 7, 7], false), Some(1.5));
+        assert_eq!(bonus(&rules, &[5, 8, 8], true), None);
+        assert_eq!(bonus(&rules, &[7, 7], true), None);
+
+        let rules = RulesBuilder::new()
+            .bonuses(Bonuses {
+                suited_21: Some(Ratio::new(2, 1)),
+                ..spanish_bonuses()
+            })
+            .build();
+
+        assert_eq!(bonus(&rules, &[5, 8, 8], true), Some(2.0));
+        assert_eq!(bonus(&rules, &[5, 8, 8], false), None);
+        assert_eq!(bonus(&rules, &[6, 7, 8], false), Some(1.5));
+        assert_eq!(bonus(&rules, &[6, 7, 8], true), Some(2.0));
+    }
+
+    #[test]
+    fn expected_bonus() {
+        let rules = RulesBuilder::new()
+            .decks(2)
+            .bonuses(spanish_bonuses())
+            .build();
+
+        assert!(approx(
+            with_expected_bonus(&rules, &[6, 7, 8], 1.0),
+            2.0 / 16.0 + 1.5 * 15.0 / 16.0
+        ));
+        assert_eq!(with_expected_bonus(&rules, &[10, 9], -1.0), -1.0);
+
+        let plain = RulesBuilder::new().build();
+        assert_eq!(with_expected_bonus(&plain, &[6, 7, 8], 1.0), 1.0);
+    }
+
+    #[test]
+    fn bonuses_add_a_little_value() {
+        assert_eq!(bonus_value(&RulesBuilder::new().build()), 0.0);
+
+        let value = bonus_value(&RulesBuilder::new().bonuses(spanish_bonuses()).build());
+
+        assert!(value > 0.0 && value < 0.005);
+    }
+}
@@ -0,0 +1,172 @@
+use rand::Rng;
+
+/// Lifetime risk of ruin is treated as settled once the bankroll grows to the point where the
+/// remaining risk drops below this
+const NEGLIGIBLE_RISK: f64 = 1e-4;
+
+/// Probability of ever losing the whole bankroll. `win_rate` and `std_dev` are per hand and
+/// `bankroll` is in the same units.
+pub fn lifetime_risk_of_ruin(win_rate: f64, std_dev: f64, bankroll: f64) -> f64 {
+    if bankroll <= 0.0 {
+        return 1.0;
+    }
+
+    if win_rate <= 0.0 {
+        return 1.0;
+    }
+
+    if std_dev <= 0.0 {
+        return 0.0;
+    }
+
+    (-2.0 * win_rate * bankroll / (std_dev * std_dev)).exp()
+}
+
+/// Probability of losing the whole bankroll at some point within `hands` hands
+pub fn trip_risk_of_ruin(win_rate: f64, std_dev: f64, bankroll: f64, hands: usize) -> f64 {
+    if bankroll <= 0.0 {
+        return 1.0;
+    }
+
+    if hands == 0 || std_dev <= 0.0 {
+        return 0.0;
+    }
+
+    let n = hands as f64;
+    let spread = std_dev * n.sqrt();
+    let drift = win_rate * n;
+
+    let risk = normal_cdf((-bankroll - drift) / spread)
+        + (-2.0 * win_rate * bankroll / (std_dev * std_dev)).exp()
+            * normal_cdf((-bankroll + drift) / spread);
+
+    risk.clamp(0.0, 1.0)
+}
+
+/// Monte Carlo estimate of the risk of ruin over `trials` bankrolls, each hand is drawn from a
+/// normal distribution. `hands` limits the length of a trip, `None` plays until the bankroll is
+/// either lost or large enough for the remaining risk to be negligible.
+pub fn simulated_risk_of_ruin<R: Rng + ?Sized>(
+    win_rate: f64,
+    std_dev: f64,
+    bankroll: f64,
+    hands: Option<usize>,
+    trials: usize,
+    rng: &mut R,
+) -> f64 {
+    if trials == 0 {
+        return 0.0;
+    }
+
+    if hands.is_none() && win_rate <= 0.0 {
+        return 1.0;
+    }
+
+    let safe_bankroll = if win_rate > 0.0 {
+        std_dev * std_dev * (1.0 / NEGLIGIBLE_RISK).ln() / (2.0 * win_rate)
+    } else {
+        f64::INFINITY
+    };
+
+    let mut ruined = 0;
+
+    for _ in 0..trials {
+        let mut current = bankroll;
+        let mut played = 0;
+
+        while current > 0.0 && current < safe_bankroll && hands.is_none_or(|h| played < h) {
+            current += win_rate + std_dev * standard_normal(rng);
+            played += 1;
+        }
+
+        if current <= 0.0 {
+            ruined += 1;
+        }
+    }
+
+    ruined as f64 / trials as f64
+}
+
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    // Box-Muller
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random::<f64>();
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz and Stegun 7.1.26, accurate to about 1.5e-7
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let y = 1.0
+        - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t
+            + 0.254829592)
+            * t
+            * (-x * x).exp();
+
+    sign * y
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn lifetime() {
+        // 1% edge, 1.15 sd, 400 units is roughly 0.24% risk of ruin
+        let risk = lifetime_risk_of_ruin(0.01, 1.15, 400.0);
+        assert!((risk - 0.0024).abs() < 0.0002);
+
+        assert_eq!(lifetime_risk_of_ruin(-0.005, 1.15, 400.0), 1.0);
+        assert_eq!(lifetime_risk_of_ruin(0.01, 1.15, 0.0), 1.0);
+    }
+
+    #[test]
+    fn trip_is_bounded_by_lifetime() {
+        let lifetime = lifetime_risk_of_ruin(0.01, 1.15, 100.0);
+
+        let short = trip_risk_of_ruin(0.01, 1.15, 100.0, 1_000);
+        let long = trip_risk_of_ruin(0.01, 1.15, 100.0, 100_000);
+
+        assert!(short < long);
+        assert!(long <= lifetime + 1e-6);
+        assert!((long - lifetime).abs() < 0.01);
+        assert_eq!(trip_risk_of_ruin(0.01, 1.15, 100.0, 0), 0.0);
+    }
+
+    #[test]
+    fn trip_with_negative_expectation() {
+        let risk = trip_risk_of_ruin(-0.005, 1.15, 50.0, 10_000);
+
+        assert!(risk > 0.5 && risk < 1.0);
+    }
+
+    #[test]
+    fn erf_values() {
+        assert!(erf(0.0).abs() < 1e-7);
+        assert!((erf(1.0) - 0.8427007929).abs() < 1e-6);
+        assert!((erf(-0.5) + 0.5204998778).abs() < 1e-6);
+    }
+
+    #[test]
+    fn simulation_agrees_with_analytic() {
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let analytic = trip_risk_of_ruin(0.02, 1.15, 20.0, 300);
+        let simulated = simulated_risk_of_ruin(0.02, 1.15, 20.0, Some(300), 2_000, &mut rng);
+        assert!((analytic - simulated).abs() < 0.04);
+
+        let analytic = lifetime_risk_of_ruin(0.1, 1.15, 12.0);
+        let simulated = simulated_risk_of_ruin(0.1, 1.15, 12.0, None, 2_000, &mut rng);
+        assert!((analytic - simulated).abs() < 0.04);
+    }
+}
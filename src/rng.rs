@@ -0,0 +1,78 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// RNG behind every seeded entry point. Unlike `StdRng` its output is fixed for a given seed
+/// across releases, so a session can be replayed from the seed alone.
+pub type SeededRng = ChaCha8Rng;
+
+pub fn seeded_rng(seed: u64) -> SeededRng {
+    SeededRng::seed_from_u64(seed)
+}
+
+/// Seed for an independent stream derived from `seed`, used to give each worker thread its own
+/// RNG. Mixed with SplitMix64 so neighbouring streams are uncorrelated.
+pub fn stream_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Counterpart of the `Random` derive drawing from the given RNG instead of the thread RNG
+pub trait RandomWith: Sized {
+    fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{GameType, RulesBuilder};
+
+    use super::*;
+
+    #[test]
+    fn same_seed_same_stream() {
+        let mut a = seeded_rng(42);
+        let mut b = seeded_rng(42);
+        let mut c = seeded_rng(43);
+
+        let a: Vec<u64> = (0..8).map(|_| a.random()).collect();
+        let b: Vec<u64> = (0..8).map(|_| b.random()).collect();
+        let c: Vec<u64> = (0..8).map(|_| c.random()).collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn stream_seeds_differ() {
+        let seeds: Vec<u64> = (0..16).map(|stream| stream_seed(1, stream)).collect();
+
+        for (i, seed) in seeds.iter().enumerate() {
+            assert!(!seeds[i + 1..].contains(seed));
+        }
+        assert_eq!(stream_seed(1, 3), seeds[3]);
+        assert_ne!(stream_seed(2, 3), seeds[3]);
+    }
+
+    #[test]
+    fn random_rules_from_seed() {
+        let a = RulesBuilder::random_with(&mut seeded_rng(7)).build();
+        let b = RulesBuilder::random_with(&mut seeded_rng(7)).build();
+
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+
+        let mut rng = seeded_rng(7);
+        let game_types: Vec<GameType> = (0..32).map(|_| GameType::random_with(&mut rng)).collect();
+        assert!(game_types.contains(&GameType::Hit17));
+        assert!(game_types.contains(&GameType::Stand17));
+    }
+
+    #[test]
+    fn random_rules_are_valid() {
+        for seed in 0..500 {
+            let rules = RulesBuilder::random_with(&mut seeded_rng(seed)).try_build();
+
+            assert!(rules.is_ok(), "seed {seed}: {rules:?}");
+        }
+    }
+}
@@ -0,0 +1,421 @@
+use crate::{
+    compute_hand,
+    payout::{blackjack, bonus, expected_blackjack, with_expected_bonus},
+    types::Rules,
+};
+
+/// A player hand at the end of the round
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerHand {
+    pub cards: Vec<u8>,
+    /// Initial bet, a doubled hand has twice this much riding on it
+    pub bet: f64,
+    pub doubled: bool,
+    /// Hands made by splitting are never a blackjack, a two card 21 counts as 21
+    pub split: bool,
+    pub surrendered: bool,
+    /// Blackjack paid 1:1 against an ace, whatever the dealer has
+    pub even_money: bool,
+    /// Whether every card is of one suit, as reported by `Decision::GotBJ`. `None` when suits
+    /// aren't tracked, suited payouts are then paid at their expected value.
+    pub suited: Option<bool>,
+}
+
+impl PlayerHand {
+    pub fn new(cards: Vec<u8>, bet: f64) -> Self {
+        Self {
+            cards,
+            bet,
+            doubled: false,
+            split: false,
+            surrendered: false,
+            even_money: false,
+            suited: None,
+        }
+    }
+
+    pub fn is_blackjack(&self) -> bool {
+        !self.split && is_blackjack(&self.cards)
+    }
+
+    fn wager(&self) -> f64 {
+        if self.doubled {
+            self.bet * 2.0
+        } else {
+            self.bet
+        }
+    }
+}
+
+/// A completed round, `insurance` is the amount of the insurance bet
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Round {
+    pub hands: Vec<PlayerHand>,
+    pub insurance: f64,
+    pub dealer: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Blackjack,
+    EvenMoney,
+    Bonus,
+    Win,
+    Push,
+    Lose,
+    Bust,
+    Surrender,
+    /// Bet handed back because the dealer's blackjack ended the round before it was made, only
+    /// with a hole card
+    Returned,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SettledHand {
+    pub outcome: Outcome,
+    pub net: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Settlement {
+    pub hands: Vec<SettledHand>,
+    pub insurance: f64,
+}
+
+impl Settlement {
+    pub fn total(&self) -> f64 {
+        self.insurance + self.hands.iter().map(|hand| hand.net).sum::<f64>()
+    }
+}
+
+/// Settles every hand and the insurance bet of a round. Against a dealer blackjack only the first
+/// hand's initial bet is lost when there is a hole card, any other bet couldn't have been made
+/// before the dealer checked. Without a hole card every bet, doubles and splits included, is lost.
+pub fn settle(round: &Round, rules: &Rules) -> Settlement {
+    let dealer_has_blackjack = is_blackjack(&round.dealer);
+    let (dealer_total, _) = compute_hand(&mut round.dealer.clone());
+
+    let hands = round
+        .hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| {
+            if !dealer_has_blackjack || hand.surrendered || hand.even_money {
+                return settle_hand(hand, dealer_total, rules);
+            }
+
+            if hand.is_blackjack() {
+                SettledHand {
+                    outcome: Outcome::Push,
+                    net: 0.0,
+                }
+            } else if rules.no_hole_card() {
+                SettledHand {
+                    outcome: Outcome::Lose,
+                    net: -hand.wager(),
+                }
+            } else if i == 0 {
+                SettledHand {
+                    outcome: Outcome::Lose,
+                    net: -hand.bet,
+                }
+            } else {
+                SettledHand {
+                    outcome: Outcome::Returned,
+                    net: 0.0,
+                }
+            }
+        })
+        .collect();
+
+    let insurance = if dealer_has_blackjack {
+        round.insurance * 2.0
+    } else {
+        -round.insurance
+    };
+
+    Settlement { hands, insurance }
+}
+
+/// Settles a hand against a dealer without a blackjack
+fn settle_hand(hand: &PlayerHand, dealer_total: u8, rules: &Rules) -> SettledHand {
+    let settled = |outcome, net| SettledHand { outcome, net };
+
+    if hand.surrendered {
+        return settled(Outcome::Surrender, -hand.bet / 2.0);
+    }
+
+    if hand.even_money {
+        return settled(Outcome::EvenMoney, hand.bet);
+    }
+
+    if hand.is_blackjack() {
+        let payout = match hand.suited {
+            Some(suited) => blackjack(rules, suited),
+            None => expected_blackjack(rules),
+        };
+
+        return settled(Outcome::Blackjack, hand.bet * payout);
+    }
+
+    let (total, valid) = compute_hand(&mut hand.cards.clone());
+
+    if !valid {
+        return settled(Outcome::Bust, -hand.wager());
+    }
+
+    let (outcome, units) = if dealer_total > 21 || total > dealer_total {
+        (Outcome::Win, 1.0)
+    } else if total < dealer_total {
+        (Outcome::Lose, -1.0)
+    } else {
+        (Outcome::Push, 0.0)
+    };
+
+    if hand.doubled {
+        return settled(outcome, units * hand.wager());
+    }
+
+    let with_bonus = match hand.suited {
+        Some(suited) => bonus(rules, &hand.cards, suited),
+        None => Some(with_expected_bonus(rules, &hand.cards, units))
+            .filter(|expected| *expected != units),
+    };
+
+    match with_bonus {
+        Some(units) => settled(Outcome::Bonus, units * hand.bet),
+        None => settled(outcome, units * hand.bet),
+    }
+}
+
+fn is_blackjack(cards: &[u8]) -> bool {
+    cards.len() == 2 && cards.iter().sum::<u8>() == 21
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{BlackjackPayout, Bonus, Bonuses, Ratio, RulesBuilder};
+
+    use super::*;
+
+    fn round(hands: Vec<PlayerHand>, dealer: Vec<u8>) -> Round {
+        Round {
+            hands,
+            insurance: 0.0,
+            dealer,
+        }
+    }
+
+    fn outcomes(settlement: &Settlement) -> Vec<(Outcome, f64)> {
+        settlement
+            .hands
+            .iter()
+            .map(|hand| (hand.outcome, hand.net))
+            .collect()
+    }
+
+    #[test]
+    fn wins_losses_and_pushes() {
+        let rules = RulesBuilder::new().build();
+        let hands = vec![
+            PlayerHand::new(vec![10, 9], 1.0),
+            PlayerHand::new(vec![10, 8], 1.0),
+            PlayerHand::new(vec![10, 7], 1.0),
+            PlayerHand::new(vec![10, 6, 8], 1.0),
+            PlayerHand {
+                doubled: true,
+                ..PlayerHand::new(vec![5, 6, 9], 2.0)
+            },
+            PlayerHand {
+                surrendered: true,
+                ..PlayerHand::new(vec![10, 6], 1.0)
+            },
+        ];
+
+        let settlement = settle(&round(hands, vec![10, 8]), &rules);
+
+        assert_eq!(
+            outcomes(&settlement),
+            vec![
+                (Outcome::Win, 1.0),
+                (Outcome::Push, 0.0),
+                (Outcome::Lose, -1.0),
+                (Outcome::Bust, -1.0),
+                (Outcome::Win, 4.0),
+                (Outcome::Surrender, -0.5),
+            ]
+        );
+        assert_eq!(settlement.total(), 2.5);
+    }
+
+    #[test]
+    fn blackjacks() {
+        let rules = RulesBuilder::new()
+            .blackjack_payout(BlackjackPayout::SixToFiveSuitedTwoToOne)
+            .build();
+        let hands = vec![
+            PlayerHand {
+                suited: Some(true),
+                ..PlayerHand::new(vec![11, 10], 5.0)
+            },
+            PlayerHand {
+                suited: Some(false),
+                ..PlayerHand::new(vec![10, 11], 5.0)
+            },
+            PlayerHand::new(vec![11, 10], 5.0),
+        ];
+
+        let settlement = settle(&round(hands, vec![10, 7]), &rules);
+
+        assert_eq!(
+            outcomes(&settlement),
+            vec![
+                (Outcome::Blackjack, 10.0),
+                (Outcome::Blackjack, 6.0),
+                (Outcome::Blackjack, 7.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_21_is_not_a_blackjack() {
+        let rules = RulesBuilder::new().build();
+        let hands = vec![
+            PlayerHand {
+                split: true,
+                ..PlayerHand::new(vec![11, 10], 1.0)
+            },
+            PlayerHand {
+                split: true,
+                ..PlayerHand::new(vec![11, 9], 1.0)
+            },
+        ];
+
+        let settlement = settle(&round(hands.clone(), vec![10, 5, 6]), &rules);
+        assert_eq!(
+            outcomes(&settlement),
+            vec![(Outcome::Push, 0.0), (Outcome::Lose, -1.0)]
+        );
+
+        let settlement = settle(&round(hands, vec![10, 10]), &rules);
+        assert_eq!(
+            outcomes(&settlement),
+            vec![(Outcome::Win, 1.0), (Outcome::Push, 0.0)]
+        );
+    }
+
+    #[test]
+    fn insurance_and_even_money() {
+        let rules = RulesBuilder::new().build();
+        let even_money = PlayerHand {
+            even_money: true,
+            ..PlayerHand::new(vec![11, 10], 2.0)
+        };
+
+        for dealer in [vec![11, 10], vec![11, 6, 10]] {
+            let settlement = settle(&round(vec![even_money.clone()], dealer), &rules);
+            assert_eq!(outcomes(&settlement), vec![(Outcome::EvenMoney, 2.0)]);
+        }
+
+        let insured = Round {
+            hands: vec![PlayerHand::new(vec![10, 9], 2.0)],
+            insurance: 1.0,
+            dealer: vec![11, 10],
+        };
+        let settlement = settle(&insured, &rules);
+        assert_eq!(settlement.insurance, 2.0);
+        assert_eq!(settlement.total(), 0.0);
+
+        let settlement = settle(
+            &Round {
+                dealer: vec![11, 9],
+                ..insured
+            },
+            &rules,
+        );
+        assert_eq!(settlement.insurance, -1.0);
+        assert_eq!(settlement.total(), -3.0);
+    }
+
+    #[test]
+    fn dealer_blackjack() {
+        let hands = vec![
+            PlayerHand {
+                split: true,
+                doubled: true,
+                ..PlayerHand::new(vec![8, 3, 10], 1.0)
+            },
+            PlayerHand {
+                split: true,
+                ..PlayerHand::new(vec![8, 10], 1.0)
+            },
+        ];
+
+        // the dealer checks for blackjack before any split or double
+        let settlement = settle(
+            &round(hands.clone(), vec![10, 11]),
+            &RulesBuilder::new().build(),
+        );
+        assert_eq!(
+            outcomes(&settlement),
+            vec![(Outcome::Lose, -1.0), (Outcome::Returned, 0.0)]
+        );
+
+        let settlement = settle(
+            &round(hands, vec![10, 11]),
+            &RulesBuilder::european().build(),
+        );
+        assert_eq!(
+            outcomes(&settlement),
+            vec![(Outcome::Lose, -2.0), (Outcome::Lose, -1.0)]
+        );
+
+        let settlement = settle(
+            &round(vec![PlayerHand::new(vec![10, 11], 1.0)], vec![11, 10]),
+            &RulesBuilder::new().build(),
+        );
+        assert_eq!(outcomes(&settlement), vec![(Outcome::Push, 0.0)]);
+    }
+
+    #[test]
+    fn bonuses() {
+        let rules = RulesBuilder::new()
+            .decks(1)
+            .bonuses(Bonuses {
+                six_seven_eight: Bonus {
+                    mixed: Some(Ratio::new(3, 2)),
+                    suited: Some(Ratio::new(2, 1)),
+                },
+                ..Default::default()
+            })
+            .build();
+        let hands = vec![
+            PlayerHand {
+                suited: Some(true),
+                ..PlayerHand::new(vec![6, 7, 8], 2.0)
+            },
+            PlayerHand {
+                suited: Some(false),
+                ..PlayerHand::new(vec![8, 7, 6], 2.0)
+            },
+            PlayerHand::new(vec![6, 8, 7], 16.0),
+            PlayerHand {
+                doubled: true,
+                ..PlayerHand::new(vec![7, 6, 8], 2.0)
+            },
+        ];
+
+        // paid whatever the dealer has
+        let settlement = settle(&round(hands, vec![10, 5, 6]), &rules);
+
+        assert_eq!(
+            outcomes(&settlement),
+            vec![
+                (Outcome::Bonus, 4.0),
+                (Outcome::Bonus, 3.0),
+                (Outcome::Bonus, 16.0 * (2.0 / 16.0 + 1.5 * 15.0 / 16.0)),
+                (Outcome::Push, 0.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use rand::{Rng, seq::SliceRandom};
+
+use crate::types::{DeckPen, Rules};
+
+pub const CARDS_PER_DECK: usize = 52;
+
+#[derive(Clone, Debug)]
+pub struct Shoe {
+    cards: Vec<u8>,
+    position: usize,
+    cut_card: usize,
+}
+
+impl Shoe {
+    /// Builds an unshuffled shoe with `Rules::decks()` worth of cards
+    pub fn new(rules: &Rules) -> Self {
+        let cards = unshuffled_cards(rules.decks());
+        let cut_card = cut_card_position(cards.len(), rules.deck_pen());
+
+        Self {
+            cards,
+            position: 0,
+            cut_card,
+        }
+    }
+
+    pub fn shuffled<R: Rng + ?Sized>(rules: &Rules, rng: &mut R) -> Self {
+        let mut shoe = Self::new(rules);
+        shoe.shuffle(rng);
+        shoe
+    }
+
+    /// Stacked shoe, cards are dealt in the given order
+    pub fn from_cards(cards: Vec<u8>, deck_pen: &DeckPen) -> Self {
+        let cut_card = cut_card_position(cards.len(), deck_pen);
+
+        Self {
+            cards,
+            position: 0,
+            cut_card,
+        }
+    }
+
+    /// Puts every dealt card back into the shoe and shuffles it
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+        self.position = 0;
+    }
+
+    pub fn deal(&mut self) -> Option<u8> {
+        let card = self.cards.get(self.position).copied()?;
+        self.position += 1;
+        Some(card)
+    }
+
+    /// True once the cut card has come out, the current round can still be finished
+    pub fn needs_shuffle(&self) -> bool {
+        self.position >= self.cut_card
+    }
+
+    pub fn total_cards(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn cards_dealt(&self) -> usize {
+        self.position
+    }
+
+    pub fn cards_remaining(&self) -> usize {
+        self.cards.len() - self.position
+    }
+
+    pub fn decks_remaining(&self) -> f32 {
+        self.cards_remaining() as f32 / CARDS_PER_DECK as f32
+    }
+
+    /// Number of cards dealt before the cut card comes out
+    pub fn cut_card(&self) -> usize {
+        self.cut_card
+    }
+}
+
+/// Every card of `decks` decks in rank order
+pub fn unshuffled_cards(decks: u8) -> Vec<u8> {
+    let mut cards = Vec::with_capacity(decks as usize * CARDS_PER_DECK);
+
+    for _ in 0..decks {
+        for card in 2..=11 {
+            let copies = if card == 10 { 16 } else { 4 };
+            cards.extend(std::iter::repeat_n(card, copies));
+        }
+    }
+
+    cards
+}
+
+/// `DeckPen::value()` is the number of cards left behind the cut card
+fn cut_card_position(total_cards: usize, deck_pen: &DeckPen) -> usize {
+    total_cards
+        .saturating_sub(deck_pen.value())
+        .max(1)
+        .min(total_cards)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::types::RulesBuilder;
+
+    use super::*;
+
+    #[test]
+    fn shoe_has_all_cards() {
+        let rules = RulesBuilder::new().decks(6).build();
+        let mut shoe = Shoe::shuffled(&rules, &mut StdRng::seed_from_u64(7));
+
+        assert_eq!(shoe.total_cards(), 312);
+        assert_eq!(shoe.decks_remaining(), 6.0);
+
+        let mut counts = [0; 12];
+        while let Some(card) = shoe.deal() {
+            counts[card as usize] += 1;
+        }
+
+        assert_eq!(shoe.cards_remaining(), 0);
+        assert_eq!(counts[10], 96);
+        for card in [2, 3, 4, 5, 6, 7, 8, 9, 11] {
+            assert_eq!(counts[card], 24);
+        }
+    }
+
+    #[test]
+    fn cut_card_from_deck_pen() {
+        let rules = RulesBuilder::new().decks(2).deck_pen(DeckPen::Half).build();
+        let mut shoe = Shoe::new(&rules);
+
+        assert_eq!(shoe.cut_card(), 78);
+
+        for _ in 0..77 {
+            shoe.deal();
+        }
+        assert!(!shoe.needs_shuffle());
+
+        shoe.deal();
+        assert!(shoe.needs_shuffle());
+        assert_eq!(shoe.cards_remaining(), 26);
+
+        shoe.shuffle(&mut StdRng::seed_from_u64(1));
+        assert!(!shoe.needs_shuffle());
+        assert_eq!(shoe.cards_remaining(), 104);
+    }
+
+    #[test]
+    fn deck_pen_larger_than_shoe() {
+        let shoe = Shoe::from_cards(vec![10, 11, 5], &DeckPen::Two);
+
+        assert_eq!(shoe.cut_card(), 1);
+    }
+
+    #[test]
+    fn same_seed_same_order() {
+        let rules = Rules::default();
+
+        let mut a = Shoe::shuffled(&rules, &mut StdRng::seed_from_u64(42));
+        let mut b = Shoe::shuffled(&rules, &mut StdRng::seed_from_u64(42));
+
+        for _ in 0..rules.decks() as usize * CARDS_PER_DECK {
+            assert_eq!(a.deal(), b.deal());
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    shoe::CARDS_PER_DECK,
+    types::{
+        BlackjackPayout, DeckPen, GameType, IsDoubleAllowed, Ratio, Rules, RulesBuilder,
+        RulesError, SplitAces,
+    },
+};
+
+/// Named penetrations, a parsed penetration landing on one of them uses it instead of `Custom`
+const NAMED_DECK_PENS: [DeckPen; 8] = [
+    DeckPen::Quater,
+    DeckPen::Half,
+    DeckPen::ThreeFourth,
+    DeckPen::One,
+    DeckPen::OneQuater,
+    DeckPen::OneAndHalf,
+    DeckPen::OneThreeFourth,
+    DeckPen::Two,
+];
+
+/// Rule each token sets, so a rule given twice is reported
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Decks,
+    GameType,
+    DoubleAfterSplit,
+    Surrender,
+    SplitAces,
+    Payout,
+    Double,
+    Splits,
+    HoleCard,
+    Penetration,
+}
+
+/// Parses the shorthand players use to describe a game, such as `6D H17 DAS LS RSA 3:2 75%`.
+/// Tokens are separated by whitespace, case insensitive and can come in any order:
+///
+/// - `6D` number of decks
+/// - `H17`, `S17`
+/// - `DAS`, `NDAS`
+/// - `LS` late surrender, `NS` no surrender. `ES` is rejected, early surrender isn't modeled.
+/// - `RSA` resplit aces, `SPA1` split aces once, `NSA` no splitting aces
+/// - `3:2`, `6:5`, any other ratio, with the suited blackjack payout appended as in `6:5+S2:1`
+/// - `DA2` double any two cards, `D9A` on 9 to 11 and soft hands, `D9` on 9 to 11, `D10` on 10
+///   and 11, `ND` no doubling
+/// - `SP4` split up to four hands
+/// - `ENHC` no hole card, `AHC` hole card
+/// - `75%` share of the shoe dealt before the shuffle
+///
+/// Missing rules take the `RulesBuilder` defaults and the result is validated like `try_build`,
+/// the first problem found is returned.
+impl FromStr for Rules {
+    type Err = RulesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut builder = RulesBuilder::new();
+        let mut decks = builder.clone().build().decks();
+        let mut penetration = None;
+        let mut seen = vec![];
+
+        for token in s.split_whitespace() {
+            let upper = token.to_ascii_uppercase();
+            let unknown = || RulesError::UnknownShorthand(token.to_string());
+
+            let field = match upper.as_str() {
+                "H17" => {
+                    builder = builder.game_type(GameType::Hit17);
+                    Field::GameType
+                }
+                "S17" => {
+                    builder = builder.game_type(GameType::Stand17);
+                    Field::GameType
+                }
+                "DAS" | "NDAS" => {
+                    builder = builder.double_after_split(upper == "DAS");
+                    Field::DoubleAfterSplit
+                }
+                "LS" | "NS" => {
+                    builder = builder.surrender(upper == "LS");
+                    Field::Surrender
+                }
+                "ES" => return Err(RulesError::UnsupportedShorthand(token.to_string())),
+                "RSA" | "SPA1" | "NSA" => {
+                    builder = builder.split_aces(match upper.as_str() {
+                        "RSA" => SplitAces::ReSplitAces,
+                        "SPA1" => SplitAces::SplitAcesOnce,
+                        _ => SplitAces::No,
+                    });
+                    Field::SplitAces
+                }
+                "ENHC" | "AHC" => {
+                    builder = builder.no_hole_card(upper == "ENHC");
+                    Field::HoleCard
+                }
+                _ => {
+                    if let Some(payout) = payout(&upper) {
+                        builder = builder.blackjack_payout(payout);
+                        Field::Payout
+                    } else if let Some(is_double_allowed) = double(&upper) {
+                        builder = builder.is_double_allowed(is_double_allowed);
+                        Field::Double
+                    } else if let Some(hands) = upper.strip_prefix("SP") {
+                        let hands: u8 = hands.parse().map_err(|_| unknown())?;
+                        let splits = hands.checked_sub(1).ok_or_else(unknown)?;
+                        builder = builder.max_splits_allowed(splits);
+                        Field::Splits
+                    } else if let Some(count) = upper.strip_suffix('D') {
+                        decks = count.parse().map_err(|_| unknown())?;
+                        builder = builder.decks(decks);
+                        Field::Decks
+                    } else if let Some(percent) = upper.strip_suffix('%') {
+                        let percent: f64 = percent.parse().map_err(|_| unknown())?;
+                        if !(0.0..=100.0).contains(&percent) {
+                            return Err(unknown());
+                        }
+                        penetration = Some(percent);
+                        Field::Penetration
+                    } else {
+                        return Err(unknown());
+                    }
+                }
+            };
+
+            if seen.contains(&field) {
+                return Err(RulesError::DuplicateShorthand(token.to_string()));
+            }
+            seen.push(field);
+        }
+
+        if let Some(percent) = penetration {
+            builder = builder.deck_pen(deck_pen(percent, decks));
+        }
+
+        builder.try_build().map_err(|mut errors| errors.remove(0))
+    }
+}
+
+/// Formats the rules in the shorthand `FromStr` reads, rules it doesn't cover such as the
+/// counting system are left out. ENHC is only written for no hole card games.
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let game_type = match self.game_type() {
+            GameType::Hit17 => "H17",
+            GameType::Stand17 => "S17",
+        };
+        let double_after_split = if self.double_after_split() {
+            "DAS"
+        } else {
+            "NDAS"
+        };
+        let surrender = if self.surrender() { "LS" } else { "NS" };
+        let split_aces = match self.split_aces() {
+            SplitAces::No => "NSA",
+            SplitAces::SplitAcesOnce => "SPA1",
+            SplitAces::ReSplitAces => "RSA",
+        };
+        let payout = self.blackjack_payout().ratio();
+        let suited = self.blackjack_payout().suited_ratio();
+        let payout = if payout == suited {
+            payout.to_string()
+        } else {
+            format!("{payout}+S{suited}")
+        };
+        let double = match self.is_double_allowed() {
+            IsDoubleAllowed::No => "ND",
+            IsDoubleAllowed::TenAndElevenOnly => "D10",
+            IsDoubleAllowed::NineAndTenOnly => "D9",
+            IsDoubleAllowed::NineTenAndAceOnly => "D9A",
+            IsDoubleAllowed::Any => "DA2",
+        };
+
+        write!(
+            f,
+            "{}D {game_type} {double_after_split} {surrender} {split_aces} {payout} {double} SP{}",
+            self.decks(),
+            self.max_splits_allowed() + 1
+        )?;
+
+        if self.no_hole_card() {
+            write!(f, " ENHC")?;
+        }
+
+        write!(f, " {}%", penetration(self.deck_pen(), self.decks()))
+    }
+}
+
+fn payout(token: &str) -> Option<BlackjackPayout> {
+    let (payout, suited) = match token.split_once("+S") {
+        Some((payout, suited)) => (ratio(payout)?, ratio(suited)?),
+        None => (ratio(token)?, ratio(token)?),
+    };
+
+    Some(BlackjackPayout::from_ratios(payout, suited))
+}
+
+fn ratio(token: &str) -> Option<Ratio> {
+    let (numerator, denominator) = token.split_once(':')?;
+
+    Some(Ratio::new(
+        numerator.parse().ok()?,
+        denominator.parse().ok()?,
+    ))
+}
+
+fn double(token: &str) -> Option<IsDoubleAllowed> {
+    match token {
+        "ND" => Some(IsDoubleAllowed::No),
+        "D10" => Some(IsDoubleAllowed::TenAndElevenOnly),
+        "D9" => Some(IsDoubleAllowed::NineAndTenOnly),
+        "D9A" => Some(IsDoubleAllowed::NineTenAndAceOnly),
+        "DA2" => Some(IsDoubleAllowed::Any),
+        _ => None,
+    }
+}
+
+/// Share of the shoe dealt before the cut card to a tenth of a percent, close enough to land on
+/// the same card when parsed back for any shoe up to eight decks
+fn penetration(deck_pen: &DeckPen, decks: u8) -> f64 {
+    let total_cards = (decks as usize * CARDS_PER_DECK) as f64;
+    let dealt = (1.0 - deck_pen.value() as f64 / total_cards).max(0.0);
+
+    (dealt * 1000.0).round() / 10.0
+}
+
+fn deck_pen(percent: f64, decks: u8) -> DeckPen {
+    let total_cards = (decks as usize * CARDS_PER_DECK) as f64;
+    let cards_behind_cut = (total_cards * (1.0 - percent / 100.0)).round() as usize;
+
+    NAMED_DECK_PENS
+        .into_iter()
+        .find(|deck_pen| deck_pen.value() == cards_behind_cut)
+        .unwrap_or(DeckPen::Custom(
+            cards_behind_cut as f32 / CARDS_PER_DECK as f32,
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_common_shorthand() {
+        let rules: Rules = "6D H17 DAS LS RSA 3:2 75%".parse().unwrap();
+
+        assert_eq!(rules.decks(), 6);
+        assert_eq!(rules.game_type(), &GameType::Hit17);
+        assert!(rules.double_after_split());
+        assert!(rules.surrender());
+        assert_eq!(rules.split_aces(), &SplitAces::ReSplitAces);
+        assert_eq!(rules.blackjack_payout(), &BlackjackPayout::ThreeToTwo);
+        assert_eq!(rules.deck_pen(), &DeckPen::OneAndHalf);
+
+        let rules: Rules = "2d s17 ndas ns spa1 6:5 d10 sp2 enhc 50%".parse().unwrap();
+
+        assert_eq!(rules.decks(), 2);
+        assert_eq!(rules.game_type(), &GameType::Stand17);
+        assert!(!rules.double_after_split());
+        assert!(!rules.surrender());
+        assert_eq!(rules.split_aces(), &SplitAces::SplitAcesOnce);
+        assert_eq!(rules.blackjack_payout(), &BlackjackPayout::SixToFive);
+        assert_eq!(
+            rules.is_double_allowed(),
+            &IsDoubleAllowed::TenAndElevenOnly
+        );
+        assert_eq!(rules.max_splits_allowed(), 1);
+        assert!(rules.no_hole_card());
+        assert_eq!(rules.deck_pen(), &DeckPen::One);
+    }
+
+    #[test]
+    fn any_payout_ratio() {
+        let rules: Rules = "6D 7:5".parse().unwrap();
+        assert_eq!(
+            rules.blackjack_payout(),
+            &BlackjackPayout::Custom {
+                payout: Ratio::new(7, 5),
+                suited: Ratio::new(7, 5)
+            }
+        );
+
+        let rules: Rules = "6D 6:5+S2:1".parse().unwrap();
+        assert_eq!(
+            rules.blackjack_payout(),
+            &BlackjackPayout::SixToFiveSuitedTwoToOne
+        );
+
+        assert_eq!(
+            "6D 3:0".parse::<Rules>(),
+            Err(RulesError::InvalidPayout(Ratio::new(3, 0)))
+        );
+    }
+
+    #[test]
+    fn missing_rules_take_the_builder_defaults() {
+        let rules: Rules = "".parse().unwrap();
+
+        assert_eq!(rules, RulesBuilder::new().build());
+    }
+
+    #[test]
+    fn formats_every_rule() {
+        assert_eq!(
+            RulesBuilder::new().build().to_string(),
+            "6D H17 DAS NS SPA1 3:2 DA2 SP4 83.3%"
+        );
+        assert_eq!(
+            RulesBuilder::european().build().to_string(),
+            "6D S17 DAS NS SPA1 3:2 D9 SP2 ENHC 75%"
+        );
+        assert_eq!(
+            RulesBuilder::online().build().to_string(),
+            "8D S17 DAS NS SPA1 3:2 DA2 SP4 0%"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for builder in [
+            RulesBuilder::new(),
+            RulesBuilder::vegas_strip(),
+            RulesBuilder::downtown(),
+            RulesBuilder::atlantic_city(),
+            RulesBuilder::reno(),
+            RulesBuilder::european(),
+            RulesBuilder::single_deck_six_to_five(),
+            RulesBuilder::macau(),
+            RulesBuilder::online(),
+            RulesBuilder::new()
+                .decks(8)
+                .blackjack_payout(BlackjackPayout::SixToFiveSuitedTwoToOne)
+                .is_double_allowed(IsDoubleAllowed::NineTenAndAceOnly)
+                .split_aces(SplitAces::No)
+                .deck_pen(DeckPen::Custom(2.5)),
+            RulesBuilder::new().blackjack_payout(BlackjackPayout::Custom {
+                payout: Ratio::new(7, 5),
+                suited: Ratio::new(3, 2),
+            }),
+        ] {
+            let rules = builder.build();
+
+            assert_eq!(rules.to_string().parse::<Rules>(), Ok(rules));
+        }
+    }
+
+    #[test]
+    fn penetration_between_named_values() {
+        let rules: Rules = "8D 70%".parse().unwrap();
+
+        // 125 of 416 cards behind the cut card
+        assert_eq!(rules.deck_pen().value(), 125);
+        assert_eq!(rules.to_string().parse::<Rules>(), Ok(rules));
+    }
+
+    #[test]
+    fn rejects_bad_shorthand() {
+        assert_eq!(
+            "6D H17 ES".parse::<Rules>(),
+            Err(RulesError::UnsupportedShorthand("ES".to_string()))
+        );
+        assert_eq!(
+            "6D H17 S17".parse::<Rules>(),
+            Err(RulesError::DuplicateShorthand("S17".to_string()))
+        );
+        assert_eq!(
+            "6D H17 XYZ".parse::<Rules>(),
+            Err(RulesError::UnknownShorthand("XYZ".to_string()))
+        );
+        assert_eq!(
+            "6D SP0".parse::<Rules>(),
+            Err(RulesError::UnknownShorthand("SP0".to_string()))
+        );
+        assert_eq!(
+            "6D 120%".parse::<Rules>(),
+            Err(RulesError::UnknownShorthand("120%".to_string()))
+        );
+        assert_eq!("12D".parse::<Rules>(), Err(RulesError::DecksOutOfRange(12)));
+    }
+}
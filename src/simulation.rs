@@ -0,0 +1,734 @@
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    bet_ramp::BetRamp,
+    compute_hand,
+    counter::Counter,
+    get_decision_without_split,
+    insurance::should_i_take_insurance,
+    rng::{seeded_rng, stream_seed},
+    settlement::{PlayerHand, Round, settle},
+    shoe::Shoe,
+    splits_chart::should_i_split,
+    statistics::Statistics,
+    surrender::should_i_surrender,
+    types::{Decision, Deviations, GameType, OtherPlayersPlayType, Rules, SplitAces},
+};
+
+/// Runs rounds at a table described by `Rules`. Other players take their seats before the hero
+/// and play according to `Rules::do_other_players_play_perfect_strategy`, the hero plays the
+/// crate's strategy with the configured deviations and sits out whenever
+/// `PlayVariation::play()` or the `BetRamp` says so. Bets are flat, one unit per round, unless
+/// a bet ramp is given.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    rules: Rules,
+    bet_ramp: BetRamp,
+}
+
+impl Simulation {
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            bet_ramp: BetRamp::flat(),
+        }
+    }
+
+    pub fn bet_ramp(mut self, val: BetRamp) -> Self {
+        self.bet_ramp = val;
+        self
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn run<R: Rng + ?Sized>(&self, rounds: usize, rng: &mut R) -> Statistics {
+        let shoe = Shoe::shuffled(&self.rules, rng);
+        let mut table = Table::new(&self.rules, &self.bet_ramp, shoe, rng);
+        let mut statistics = Statistics::default();
+
+        for _ in 0..rounds {
+            match table.play_round() {
+                Some(round) => statistics.record(round.true_count, round.bet as f64, round.net),
+                None => statistics.record_sat_out(),
+            }
+        }
+
+        statistics
+    }
+
+    /// Same as `run` with a `SeededRng`, the same seed always gives the same statistics
+    pub fn run_seeded(&self, rounds: usize, seed: u64) -> Statistics {
+        self.run(rounds, &mut seeded_rng(seed))
+    }
+
+    /// Splits the rounds across `threads` threads, each with its own shoe and an RNG seeded from
+    /// `seed` and the thread's index, and merges their statistics. The result only depends on the
+    /// seed and the number of threads.
+    pub fn run_parallel(&self, rounds: usize, seed: u64, threads: usize) -> Statistics {
+        let threads = threads.clamp(1, rounds.max(1));
+
+        let results: Vec<Statistics> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|thread| {
+                    let rounds = rounds / threads + usize::from(thread < rounds % threads);
+                    let seed = stream_seed(seed, thread as u64);
+
+                    scope.spawn(move || self.run_seeded(rounds, seed))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("simulation thread panicked"))
+                .collect()
+        });
+
+        let mut statistics = Statistics::default();
+        for result in &results {
+            statistics.merge(result);
+        }
+
+        statistics
+    }
+
+    /// `run_parallel` using every available core
+    pub fn run_parallel_on_all_cores(&self, rounds: usize, seed: u64) -> Statistics {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+
+        self.run_parallel(rounds, seed, threads)
+    }
+}
+
+/// Hero's result for a round, `net` is in units and already includes the bet size
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RoundResult {
+    true_count: isize,
+    bet: u32,
+    net: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Seat {
+    Hero,
+    Other(OtherPlayersPlayType),
+}
+
+#[derive(Clone, Debug)]
+struct PlayedHand {
+    cards: Vec<u8>,
+    doubled: bool,
+    split: bool,
+    split_aces: bool,
+    surrendered: bool,
+}
+
+impl PlayedHand {
+    fn new(cards: Vec<u8>) -> Self {
+        Self {
+            cards,
+            doubled: false,
+            split: false,
+            split_aces: false,
+            surrendered: false,
+        }
+    }
+
+    fn total(&self) -> (u8, bool) {
+        compute_hand(&mut self.cards.clone())
+    }
+
+    fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0] == self.cards[1]
+    }
+
+    fn is_live(&self) -> bool {
+        !self.surrendered && self.total().1
+    }
+
+    /// Hand as settled, the shoe doesn't track suits so suited payouts are paid at their expected
+    /// value
+    fn settled(&self) -> PlayerHand {
+        PlayerHand {
+            doubled: self.doubled,
+            split: self.split,
+            surrendered: self.surrendered,
+            ..PlayerHand::new(self.cards.clone(), 1.0)
+        }
+    }
+}
+
+struct Table<'a, R: Rng + ?Sized> {
+    rules: &'a Rules,
+    bet_ramp: &'a BetRamp,
+    // what the other players consider perfect basic strategy
+    basic_rules: Rules,
+    shoe: Shoe,
+    counter: Counter,
+    rng: &'a mut R,
+}
+
+impl<'a, R: Rng + ?Sized> Table<'a, R> {
+    fn new(rules: &'a Rules, bet_ramp: &'a BetRamp, shoe: Shoe, rng: &'a mut R) -> Self {
+        let mut basic_rules = rules.clone();
+        basic_rules.set_enable_deviations(Deviations::None);
+        basic_rules.set_custom_index_plays(vec![]);
+
+        Self {
+            rules,
+            bet_ramp,
+            basic_rules,
+            shoe,
+            counter: Counter::new(rules),
+            rng,
+        }
+    }
+
+    /// `None` when the hero sat out
+    fn play_round(&mut self) -> Option<RoundResult> {
+        if self.shoe.needs_shuffle() {
+            self.shuffle();
+        }
+
+        let true_count = self.counter.true_count();
+        let bet = self
+            .rules
+            .play_variation()
+            .play(true_count as i32, self.counter.running_count() as i32)
+            .then(|| self.bet_ramp.bet(true_count))
+            .flatten();
+        let hero_plays = bet.is_some();
+
+        let mut seats = vec![
+            Seat::Other(self.rules.do_other_players_play_perfect_strategy());
+            self.rules.number_of_other_players() as usize
+        ];
+
+        if hero_plays {
+            seats.push(Seat::Hero);
+        }
+
+        let no_hole_card = self.rules.no_hole_card();
+        let mut starting_hands = vec![vec![]; seats.len()];
+        let mut dealer = vec![];
+
+        for _ in 0..2 {
+            for hand in starting_hands.iter_mut() {
+                hand.push(self.draw());
+            }
+
+            if dealer.is_empty() {
+                dealer.push(self.draw());
+            } else if !no_hole_card {
+                dealer.push(self.draw_hidden());
+            }
+        }
+
+        let dealer_up_card = dealer[0];
+
+        let insured = hero_plays
+            && dealer_up_card == 11
+            && should_i_take_insurance(
+                dealer_up_card,
+                self.counter.running_count(),
+                self.counter.true_count(),
+                self.rules,
+            );
+        let insurance = if insured { 0.5 } else { 0.0 };
+
+        if !no_hole_card && is_blackjack(&dealer) {
+            self.counter.observe(dealer[1]);
+
+            let bet = bet?;
+            let hero = starting_hands.last().expect("hero is seated");
+            let round = Round {
+                hands: vec![PlayedHand::new(hero.clone()).settled()],
+                insurance,
+                dealer,
+            };
+
+            return Some(RoundResult {
+                true_count,
+                bet,
+                net: bet as f64 * settle(&round, self.rules).total(),
+            });
+        }
+
+        let mut hero_hands = vec![];
+        let mut any_live_hand = false;
+
+        for (seat, cards) in seats.iter().zip(starting_hands) {
+            if is_blackjack(&cards) {
+                if *seat == Seat::Hero {
+                    hero_hands = vec![PlayedHand::new(cards)];
+                }
+                continue;
+            }
+
+            let hands = self.play_hand(*seat, cards, dealer_up_card);
+            any_live_hand |= hands.iter().any(PlayedHand::is_live);
+
+            if *seat == Seat::Hero {
+                hero_hands = hands;
+            }
+        }
+
+        if no_hole_card {
+            dealer.push(self.draw());
+        } else {
+            self.counter.observe(dealer[1]);
+        }
+
+        if any_live_hand && !is_blackjack(&dealer) {
+            self.play_dealer(&mut dealer);
+        }
+
+        let bet = bet?;
+        let round = Round {
+            hands: hero_hands.iter().map(PlayedHand::settled).collect(),
+            insurance,
+            dealer,
+        };
+
+        Some(RoundResult {
+            true_count,
+            bet,
+            net: bet as f64 * settle(&round, self.rules).total(),
+        })
+    }
+
+    fn play_hand(&mut self, seat: Seat, cards: Vec<u8>, dealer_up_card: u8) -> Vec<PlayedHand> {
+        let mut queue = vec![PlayedHand::new(cards)];
+        let mut played = vec![];
+        let mut splits = 0;
+
+        while let Some(mut hand) = queue.pop() {
+            loop {
+                if hand.cards.len() == 1 {
+                    hand.cards.push(self.draw());
+                }
+
+                let (total, valid) = hand.total();
+
+                if !valid || total == 21 {
+                    break;
+                }
+
+                let can_split = hand.is_pair()
+                    && splits < self.rules.max_splits_allowed()
+                    && (hand.cards[0] != 11 || self.can_split_aces(&hand));
+
+                if hand.split_aces && !can_split {
+                    break;
+                }
+
+                let decision = self.decide(seat, &hand, dealer_up_card, can_split);
+
+                match decision {
+                    Decision::Hit => hand.cards.push(self.draw()),
+                    Decision::Double => {
+                        hand.doubled = true;
+                        hand.cards.push(self.draw());
+                        break;
+                    }
+                    Decision::Split => {
+                        splits += 1;
+
+                        let card = hand.cards.pop().expect("pair has two cards");
+                        hand.split = true;
+                        hand.split_aces = card == 11;
+
+                        queue.push(hand.clone());
+                    }
+                    Decision::Surrender => {
+                        hand.surrendered = true;
+                        break;
+                    }
+                    Decision::Stand | Decision::GotBJ { .. } => break,
+                }
+            }
+
+            played.push(hand);
+        }
+
+        played
+    }
+
+    fn can_split_aces(&self, hand: &PlayedHand) -> bool {
+        match self.rules.split_aces() {
+            SplitAces::No => false,
+            SplitAces::SplitAcesOnce => !hand.split_aces,
+            SplitAces::ReSplitAces => true,
+        }
+    }
+
+    fn decide(
+        &mut self,
+        seat: Seat,
+        hand: &PlayedHand,
+        dealer_up_card: u8,
+        can_split: bool,
+    ) -> Decision {
+        let (rules, running_count, true_count) = match seat {
+            Seat::Hero => (
+                self.rules,
+                self.counter.running_count(),
+                self.counter.true_count(),
+            ),
+            Seat::Other(OtherPlayersPlayType::PerfectBasicStrategy) => (&self.basic_rules, 0, 0),
+            Seat::Other(OtherPlayersPlayType::Random) => {
+                return *[Decision::Hit, Decision::Stand]
+                    .choose(self.rng)
+                    .expect("non empty");
+            }
+            Seat::Other(OtherPlayersPlayType::NoBustStrategy) => {
+                let (total, _) = hand.total();
+
+                return if total <= 11 {
+                    Decision::Hit
+                } else {
+                    Decision::Stand
+                };
+            }
+        };
+
+        let cards = &hand.cards;
+        let can_double = cards.len() == 2
+            && !rules.is_double_allowed().no()
+            && (!hand.split || rules.double_after_split());
+
+        if cards.len() == 2
+            && !hand.split
+            && should_i_surrender(cards, dealer_up_card, running_count, true_count, rules)
+        {
+            return Decision::Surrender;
+        }
+
+        if can_split && should_i_split(cards, dealer_up_card, running_count, true_count, rules) {
+            return Decision::Split;
+        }
+
+        let decision =
+            get_decision_without_split(cards, dealer_up_card, running_count, true_count, rules);
+
+        if decision == Decision::Double && !can_double {
+            let (total, _) = hand.total();
+
+            // soft 18 and 19 stand when they can't double
+            return if total >= 18 {
+                Decision::Stand
+            } else {
+                Decision::Hit
+            };
+        }
+
+        decision
+    }
+
+    fn play_dealer(&mut self, dealer: &mut Vec<u8>) {
+        loop {
+            let mut tmp = dealer.clone();
+            let (total, _) = compute_hand(&mut tmp);
+            let soft = tmp.contains(&11);
+
+            if total < 17 || (total == 17 && soft && self.rules.game_type() == &GameType::Hit17) {
+                dealer.push(self.draw());
+                continue;
+            }
+
+            break;
+        }
+    }
+
+    fn shuffle(&mut self) {
+        self.shoe.shuffle(self.rng);
+        self.counter.reset();
+    }
+
+    fn draw(&mut self) -> u8 {
+        let card = self.draw_hidden();
+        self.counter.observe(card);
+        card
+    }
+
+    /// Dealer's hole card, observed once it is turned over
+    fn draw_hidden(&mut self) -> u8 {
+        match self.shoe.deal() {
+            Some(card) => card,
+            None => {
+                // ran out of cards mid round
+                self.shuffle();
+                self.shoe.deal().expect("freshly shuffled shoe has cards")
+            }
+        }
+    }
+}
+
+fn is_blackjack(cards: &[u8]) -> bool {
+    cards.len() == 2 && cards.iter().sum::<u8>() == 21
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{
+        index_plays::{Action, Comparison, Count, Hand, IndexPlay},
+        types::{DeckPen, PlayVariation, RulesBuilder},
+    };
+
+    use super::*;
+
+    #[test]
+    fn flat_bet_simulation() {
+        let rules = RulesBuilder::new()
+            .number_of_other_players(2)
+            .surrender(true)
+            .build();
+        let result = Simulation::new(rules).run(50_000, &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(result.rounds(), 50_000);
+        assert_eq!(result.hands_played(), 50_000);
+        assert!(result.win_rate() > -0.03 && result.win_rate() < 0.02);
+        assert!(result.std_dev() > 1.0 && result.std_dev() < 1.3);
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let rules = RulesBuilder::new()
+            .number_of_other_players(3)
+            .do_other_players_play_perfect_strategy(OtherPlayersPlayType::Random)
+            .enable_deviations(Deviations::Extended4)
+            .build();
+        let simulation = Simulation::new(rules).bet_ramp(BetRamp::one_to_eight());
+
+        let a = simulation.run_seeded(5_000, 11);
+        let b = simulation.run_seeded(5_000, 11);
+        let c = simulation.run_seeded(5_000, 12);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn parallel_runs_are_reproducible() {
+        let simulation = Simulation::new(RulesBuilder::new().build());
+
+        let a = simulation.run_parallel(10_001, 3, 4);
+        let b = simulation.run_parallel(10_001, 3, 4);
+
+        assert_eq!(a.rounds(), 10_001);
+        assert_eq!(a, b);
+        assert_ne!(a, simulation.run_parallel(10_001, 4, 4));
+    }
+
+    #[test]
+    fn single_thread_matches_its_stream() {
+        let simulation = Simulation::new(RulesBuilder::new().build());
+
+        assert_eq!(
+            simulation.run_parallel(2_000, 9, 1),
+            simulation.run_seeded(2_000, stream_seed(9, 0))
+        );
+        assert_eq!(simulation.run_parallel(3, 9, 8).rounds(), 3);
+        assert_eq!(simulation.run_parallel(0, 9, 8).rounds(), 0);
+    }
+
+    #[test]
+    fn sits_out_according_to_play_variation() {
+        let rules = RulesBuilder::new()
+            .play_variation(PlayVariation::PlayOnlyPositiveTrueCounts)
+            .do_other_players_play_perfect_strategy(OtherPlayersPlayType::Random)
+            .build();
+        let result = Simulation::new(rules).run(10_000, &mut StdRng::seed_from_u64(2));
+
+        assert_eq!(result.rounds(), 10_000);
+        assert!(result.hands_played() > 0);
+        assert!(result.hands_played() < 5_000);
+    }
+
+    #[test]
+    fn variable_betting() {
+        let rules = RulesBuilder::new().number_of_other_players(0).build();
+        let flat = Simulation::new(rules.clone()).run(20_000, &mut StdRng::seed_from_u64(4));
+        let spread = Simulation::new(rules)
+            .bet_ramp(BetRamp::one_to_sixteen_wonging())
+            .run(20_000, &mut StdRng::seed_from_u64(4));
+
+        assert_eq!(flat.average_bet(), 1.0);
+        assert!((flat.edge() - flat.win_rate()).abs() < 1e-9);
+
+        assert!(spread.hands_played() < 20_000);
+        assert!(spread.average_bet() > 1.0);
+        assert!(spread.std_dev() > flat.std_dev());
+    }
+
+    #[test]
+    fn risk_of_ruin_from_rules_and_bet_ramp() {
+        let rules = RulesBuilder::new().number_of_other_players(0).build();
+        let result = Simulation::new(rules)
+            .bet_ramp(BetRamp::one_to_twelve())
+            .run(20_000, &mut StdRng::seed_from_u64(6));
+
+        let small = result.risk_of_ruin(100.0, Some(10_000));
+        let large = result.risk_of_ruin(1_000.0, Some(10_000));
+
+        assert!(small > large);
+        assert!(result.risk_of_ruin(1_000.0, None) >= large);
+    }
+
+    #[test]
+    fn statistics_by_true_count() {
+        let rules = RulesBuilder::new().number_of_other_players(0).build();
+        let result = Simulation::new(rules).run(20_000, &mut StdRng::seed_from_u64(7));
+
+        let hands: usize = result.by_true_count().values().map(|s| s.count()).sum();
+        assert_eq!(hands, result.hands_played());
+
+        let (low, high) = result.confidence_interval();
+        assert!(low < result.win_rate() && result.win_rate() < high);
+        assert!(result.by_true_count().contains_key(&0));
+    }
+
+    #[test]
+    fn other_player_strategies() {
+        for play_type in [
+            OtherPlayersPlayType::PerfectBasicStrategy,
+            OtherPlayersPlayType::Random,
+            OtherPlayersPlayType::NoBustStrategy,
+        ] {
+            let rules = RulesBuilder::new()
+                .number_of_other_players(4)
+                .do_other_players_play_perfect_strategy(play_type)
+                .build();
+            let result = Simulation::new(rules).run(2_000, &mut StdRng::seed_from_u64(3));
+
+            assert_eq!(result.hands_played(), 2_000);
+        }
+    }
+
+    #[test]
+    fn other_players_ignore_custom_plays() {
+        let rules = RulesBuilder::new()
+            .custom_index_plays(vec![IndexPlay::new(
+                Hand::Hard(16),
+                10,
+                Action::Stand,
+                Count::Running,
+                Comparison::AtLeast,
+                0,
+            )])
+            .build();
+        let ramp = BetRamp::flat();
+        let mut rng = StdRng::seed_from_u64(0);
+        let shoe = Shoe::from_cards(vec![], &DeckPen::Custom(0.0));
+        let mut table = Table::new(&rules, &ramp, shoe, &mut rng);
+        let hand = PlayedHand::new(vec![10, 6]);
+
+        assert_eq!(table.decide(Seat::Hero, &hand, 10, true), Decision::Stand);
+        assert_eq!(
+            table.decide(
+                Seat::Other(OtherPlayersPlayType::PerfectBasicStrategy),
+                &hand,
+                10,
+                true
+            ),
+            Decision::Hit
+        );
+    }
+
+    #[test]
+    fn dealer_hits_soft_17_only_in_hit17() {
+        let mut rules = Rules::default();
+        let ramp = BetRamp::flat();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let shoe = Shoe::from_cards(vec![4, 10, 10], &DeckPen::Custom(0.0));
+        let mut table = Table::new(&rules, &ramp, shoe, &mut rng);
+        let mut dealer = vec![11, 6];
+        table.play_dealer(&mut dealer);
+        assert_eq!(dealer, vec![11, 6, 4]);
+
+        rules.set_game_type(GameType::Stand17);
+        let shoe = Shoe::from_cards(vec![4, 10, 10], &DeckPen::Custom(0.0));
+        let mut table = Table::new(&rules, &ramp, shoe, &mut rng);
+        let mut dealer = vec![11, 6];
+        table.play_dealer(&mut dealer);
+        assert_eq!(dealer, vec![11, 6]);
+    }
+
+    #[test]
+    fn hero_splits_and_doubles() {
+        let rules = RulesBuilder::new().number_of_other_players(0).build();
+        let ramp = BetRamp::flat();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // hero 8 8 against a dealer 16, the first split hand doubles 8 3, the second stands on 17
+        let shoe = Shoe::from_cards(vec![8, 6, 8, 10, 3, 10, 9, 10], &DeckPen::Custom(0.0));
+        let mut table = Table::new(&rules, &ramp, shoe, &mut rng);
+
+        // dealer busts
+        assert_eq!(
+            table.play_round(),
+            Some(RoundResult {
+                true_count: 0,
+                bet: 1,
+                net: 2.0 + 1.0
+            })
+        );
+    }
+
+    #[test]
+    fn no_hole_card_dealer_blackjack_after_players_act() {
+        let rules = RulesBuilder::european().number_of_other_players(0).build();
+        let ramp = BetRamp::flat();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // hero 11 against an ace hits to 20 instead of doubling, then the dealer draws a ten
+        let shoe = Shoe::from_cards(vec![6, 11, 5, 9, 10], &DeckPen::Custom(0.0));
+        let mut table = Table::new(&rules, &ramp, shoe, &mut rng);
+
+        assert_eq!(
+            table.play_round(),
+            Some(RoundResult {
+                true_count: 0,
+                bet: 1,
+                net: -1.0
+            })
+        );
+        assert_eq!(table.shoe.cards_remaining(), 0);
+    }
+
+    #[test]
+    fn settles_played_hands() {
+        let rules = RulesBuilder::new().build();
+        let net = |hand: &PlayedHand, dealer: Vec<u8>| {
+            let round = Round {
+                hands: vec![hand.settled()],
+                insurance: 0.0,
+                dealer,
+            };
+
+            settle(&round, &rules).total()
+        };
+
+        let mut hand = PlayedHand::new(vec![10, 9]);
+        assert_eq!(net(&hand, vec![10, 8]), 1.0);
+        assert_eq!(net(&hand, vec![10, 9]), 0.0);
+        assert_eq!(net(&hand, vec![10, 10]), -1.0);
+        assert_eq!(net(&hand, vec![10, 6, 6]), 1.0);
+
+        hand.doubled = true;
+        hand.cards.push(5);
+        assert_eq!(net(&hand, vec![10, 6, 6]), -2.0);
+
+        hand.surrendered = true;
+        assert_eq!(net(&hand, vec![10, 6, 6]), -0.5);
+
+        // split aces making 21 aren't a blackjack
+        let mut hand = PlayedHand::new(vec![11, 10]);
+        hand.split = true;
+        assert_eq!(net(&hand, vec![10, 6, 5]), 0.0);
+    }
+}
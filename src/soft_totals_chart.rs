@@ -2,16 +2,21 @@
 
 use crate::{
     compute_hand,
-    types::{Decision, GameType, Rules},
+    counter::TrueCount,
+    index_plays::{Action, deviation},
+    types::{Decision, Rules},
 };
 
 pub fn soft_totals_chart(
     cards_in_hand: &Vec<u8>,
     dealer_up_card: u8,
     running_count: isize,
-    true_count: isize,
+    true_count: impl Into<TrueCount>,
     rules: &Rules,
 ) -> Option<Decision> {
+    let (running_count, true_count) =
+        rules.effective_exact_counts(running_count, true_count.into());
+
     let mut tmp = cards_in_hand.clone();
 
     let (total, res) = compute_hand(&mut tmp);
@@ -24,6 +29,22 @@ pub fn soft_totals_chart(
         return Some(Decision::Hit);
     }
 
+    let can_double = rules.is_double_allowed().any() && cards_in_hand.len() == 2;
+
+    match deviation(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+        &[Action::Hit, Action::Stand, Action::Double],
+    ) {
+        Some(Action::Hit) => return Some(Decision::Hit),
+        Some(Action::Stand) => return Some(Decision::Stand),
+        Some(Action::Double) if can_double => return Some(Decision::Double),
+        _ => (),
+    }
+
     // A9
     if total == 20 {
         return Some(Decision::Stand);
@@ -31,33 +52,8 @@ pub fn soft_totals_chart(
 
     // A8
     if total == 19 {
-        if cards_in_hand.len() == 2 {
-            if rules.enable_deviations() > 0 {
-                match rules.game_type() {
-                    GameType::Hit17 if running_count < 0 && dealer_up_card == 6 => {
-                        return Some(Decision::Stand);
-                    }
-                    GameType::Stand17 => {
-                        if dealer_up_card == 6 && rules.is_double_allowed().any() {
-                            if true_count >= 1 {
-                                return Some(Decision::Double);
-                            }
-
-                            return Some(Decision::Stand);
-                        }
-                    }
-
-                    _ => (),
-                };
-            }
-
-            if (dealer_up_card == 6
-                || (true_count >= 3 && dealer_up_card == 4 && rules.enable_deviations() > 0)
-                || (true_count >= 1 && dealer_up_card == 5 && rules.enable_deviations() > 0))
-                && rules.is_double_allowed().any()
-            {
-                return Some(Decision::Double);
-            }
+        if dealer_up_card == 6 && can_double {
+            return Some(Decision::Double);
         }
 
         return Some(Decision::Stand);
@@ -78,12 +74,11 @@ pub fn soft_totals_chart(
         }
     }
 
-    // A6
+    // A6, single deck doubles against a 2 as well
     if total == 17 {
-        if rules.is_double_allowed().any()
+        if can_double
             && ((dealer_up_card >= 3 && dealer_up_card <= 6)
-                || (dealer_up_card == 2 && true_count >= 1 && rules.enable_deviations() > 0))
-            && cards_in_hand.len() == 2
+                || (dealer_up_card == 2 && rules.decks() == 1))
         {
             return Some(Decision::Double);
         }
@@ -104,11 +99,11 @@ pub fn soft_totals_chart(
         return Some(Decision::Hit);
     }
 
-    // A3 and A2
+    // A3 and A2, single deck doubles against a 4 as well
     if total == 14 || total == 13 {
         if rules.is_double_allowed().any()
-            && dealer_up_card >= 5
-            && dealer_up_card <= 6
+            && ((dealer_up_card >= 5 && dealer_up_card <= 6)
+                || (dealer_up_card == 4 && rules.decks() == 1))
             && cards_in_hand.len() == 2
         {
             return Some(Decision::Double);
@@ -122,7 +117,7 @@ pub fn soft_totals_chart(
 
 #[cfg(test)]
 mod tests {
-    use crate::types::Deviations;
+    use crate::types::{Deviations, GameType, RulesBuilder};
 
     use super::*;
 
@@ -515,4 +510,47 @@ mod tests {
             Some(Decision::Double)
         );
     }
+
+    #[test]
+    fn soft_totals_single_deck() {
+        let single = RulesBuilder::new()
+            .decks(1)
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Standard)
+            .build();
+        let shoe = RulesBuilder::new()
+            .decks(6)
+            .game_type(GameType::Stand17)
+            .enable_deviations(Deviations::Standard)
+            .build();
+
+        assert_eq!(
+            soft_totals_chart(&vec![11, 8], 6, 0, 0, &single),
+            Some(Decision::Double)
+        );
+        assert_eq!(
+            soft_totals_chart(&vec![11, 8], 6, 0, 0, &shoe),
+            Some(Decision::Stand)
+        );
+
+        assert_eq!(
+            soft_totals_chart(&vec![11, 6], 2, 0, 0, &single),
+            Some(Decision::Double)
+        );
+        assert_eq!(
+            soft_totals_chart(&vec![11, 6], 2, 0, 0, &shoe),
+            Some(Decision::Hit)
+        );
+
+        for cards in [vec![11, 2], vec![11, 3]] {
+            assert_eq!(
+                soft_totals_chart(&cards, 4, 0, 0, &single),
+                Some(Decision::Double)
+            );
+            assert_eq!(
+                soft_totals_chart(&cards, 4, 0, 0, &shoe),
+                Some(Decision::Hit)
+            );
+        }
+    }
 }
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::trainer::Question;
+
+const INITIAL_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+
+/// SM-2 answer quality for a correct and a wrong answer
+const CORRECT_QUALITY: u8 = 4;
+const WRONG_QUALITY: u8 = 1;
+
+/// A hand, up card and count the player got wrong at least once, scheduled with SM-2
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub question: Question,
+    pub ease: f64,
+    /// Questions to wait after the last review
+    pub interval: u64,
+    pub repetitions: u32,
+    pub due: u64,
+    pub lapses: u32,
+}
+
+impl ReviewItem {
+    fn new(question: Question) -> Self {
+        Self {
+            question,
+            ease: INITIAL_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: 0,
+            lapses: 0,
+        }
+    }
+
+    /// SM-2 update for an answer quality from 0 to 5, answers below 3 start the item over
+    fn review(&mut self, quality: u8, now: u64) {
+        let quality = quality.min(5);
+
+        if quality < 3 {
+            self.repetitions = 0;
+            self.interval = 1;
+            self.lapses += 1;
+        } else {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u64,
+            };
+            self.repetitions += 1;
+        }
+
+        let miss = (5 - quality) as f64;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+        self.due = now + self.interval;
+    }
+}
+
+/// Cells the player missed, rescheduled with SM-2. Time is counted in answered questions rather
+/// than days so a single session already brings mistakes back. Serializable so progress can be
+/// saved between sessions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewSchedule {
+    items: Vec<ReviewItem>,
+    answered: u64,
+}
+
+impl ReviewSchedule {
+    /// Records an answer, cells are only tracked once they have been answered wrong
+    pub fn record(&mut self, question: &Question, correct: bool) {
+        let quality = if correct {
+            CORRECT_QUALITY
+        } else {
+            WRONG_QUALITY
+        };
+
+        self.record_with_quality(question, quality, !correct);
+    }
+
+    /// Records an answer graded on the SM-2 scale from 0 to 5
+    pub fn record_quality(&mut self, question: &Question, quality: u8) {
+        self.record_with_quality(question, quality, quality < 3);
+    }
+
+    /// Most overdue cell, if any is due
+    pub fn next_due(&self) -> Option<&Question> {
+        self.items
+            .iter()
+            .filter(|item| item.due <= self.answered)
+            .min_by_key(|item| item.due)
+            .map(|item| &item.question)
+    }
+
+    pub fn due_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.due <= self.answered)
+            .count()
+    }
+
+    pub fn items(&self) -> &[ReviewItem] {
+        &self.items
+    }
+
+    pub fn answered(&self) -> u64 {
+        self.answered
+    }
+
+    fn record_with_quality(&mut self, question: &Question, quality: u8, track: bool) {
+        self.answered += 1;
+
+        let question = normalized(question);
+
+        let index = match self.items.iter().position(|item| item.question == question) {
+            Some(index) => index,
+            None if track => {
+                self.items.push(ReviewItem::new(question));
+                self.items.len() - 1
+            }
+            None => return,
+        };
+
+        self.items[index].review(quality, self.answered);
+    }
+}
+
+/// Same hand regardless of the order the cards were dealt in
+fn normalized(question: &Question) -> Question {
+    let mut question = question.clone();
+    question.cards.sort_unstable();
+    question
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn soft_19_v_6() -> Question {
+        Question {
+            cards: vec![11, 8],
+            dealer_up_card: 6,
+            running_count: 0,
+            true_count: 0,
+        }
+    }
+
+    fn hard_12_v_2() -> Question {
+        Question {
+            cards: vec![10, 2],
+            dealer_up_card: 2,
+            running_count: 0,
+            true_count: 0,
+        }
+    }
+
+    #[test]
+    fn only_mistakes_are_tracked() {
+        let mut schedule = ReviewSchedule::default();
+
+        schedule.record(&hard_12_v_2(), true);
+        assert!(schedule.items().is_empty());
+
+        schedule.record(&soft_19_v_6(), false);
+        assert_eq!(schedule.items().len(), 1);
+        assert_eq!(schedule.items()[0].lapses, 1);
+        assert_eq!(schedule.answered(), 2);
+    }
+
+    #[test]
+    fn missed_cells_come_back_sooner() {
+        let mut schedule = ReviewSchedule::default();
+        let question = soft_19_v_6();
+
+        schedule.record(&question, false);
+        assert!(schedule.next_due().is_none());
+
+        schedule.record(&hard_12_v_2(), true);
+        assert_eq!(schedule.next_due(), Some(&normalized(&question)));
+
+        // intervals grow 1, 6, then by the ease factor which the miss lowered to 1.96
+        schedule.record(&question, true);
+        assert_eq!(schedule.items()[0].interval, 1);
+        schedule.record(&question, true);
+        assert_eq!(schedule.items()[0].interval, 6);
+        schedule.record(&question, true);
+        assert_eq!(schedule.items()[0].interval, 12);
+        assert_eq!(schedule.due_count(), 0);
+
+        schedule.record(&question, false);
+        assert_eq!(schedule.items()[0].interval, 1);
+        assert_eq!(schedule.items()[0].repetitions, 0);
+        assert_eq!(schedule.items()[0].lapses, 2);
+    }
+
+    #[test]
+    fn ease_factor() {
+        let mut item = ReviewItem::new(soft_19_v_6());
+
+        item.review(5, 0);
+        assert!((item.ease - 2.6).abs() < 1e-9);
+
+        for _ in 0..10 {
+            item.review(0, 0);
+        }
+        assert_eq!(item.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn card_order_does_not_matter() {
+        let mut schedule = ReviewSchedule::default();
+        let mut reversed = soft_19_v_6();
+        reversed.cards.reverse();
+
+        schedule.record(&soft_19_v_6(), false);
+        schedule.record(&reversed, false);
+
+        assert_eq!(schedule.items().len(), 1);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut schedule = ReviewSchedule::default();
+        schedule.record(&soft_19_v_6(), false);
+        schedule.record(&hard_12_v_2(), true);
+
+        let json = serde_json::to_string(&schedule).unwrap();
+        let restored: ReviewSchedule = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, schedule);
+    }
+}
@@ -1,30 +1,42 @@
-use crate::types::Rules;
+use crate::{
+    counter::TrueCount,
+    index_plays::{Action, deviation},
+    types::Rules,
+};
 
 pub fn should_i_split(
     cards_in_hand: &Vec<u8>,
     dealer_up_card: u8,
-    true_count: isize,
+    running_count: isize,
+    true_count: impl Into<TrueCount>,
     rules: &Rules,
 ) -> bool {
+    let (running_count, true_count) =
+        rules.effective_exact_counts(running_count, true_count.into());
+
     if cards_in_hand.len() != 2 || cards_in_hand[0] != cards_in_hand[1] {
         return false;
     }
 
     let pc = cards_in_hand[0];
 
-    // 11 & 8
+    // 11 & 8, without a hole card both hands would be lost to a dealer blackjack
     if pc == 11 || pc == 8 {
-        return true;
+        return !(rules.no_hole_card()
+            && (dealer_up_card == 11 || (pc == 8 && dealer_up_card == 10)));
     }
 
-    // 10
-    if pc == 10 && rules.enable_deviations() > 0 {
-        if (true_count >= 4 && dealer_up_card == 6)
-            || (true_count >= 5 && dealer_up_card == 5)
-            || (true_count >= 6 && dealer_up_card == 4)
-        {
-            return true;
-        }
+    if deviation(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+        &[Action::Split],
+    )
+    .is_some()
+    {
+        return true;
     }
 
     // 9
@@ -35,11 +47,25 @@ pub fn should_i_split(
         return true;
     }
 
+    let single_deck = rules.decks() == 1;
+
+    // single deck with double after split also splits 3s and 7s against an 8, 6s against a 7 and
+    // 4s against a 4
+    if single_deck
+        && rules.double_after_split()
+        && (((pc == 3 || pc == 7) && dealer_up_card == 8)
+            || (pc == 6 && dealer_up_card == 7)
+            || (pc == 4 && dealer_up_card == 4))
+    {
+        return true;
+    }
+
     // 7, 2 and 3
     if (pc == 7 || pc == 2 || pc == 3) && dealer_up_card >= 2 && dealer_up_card <= 7 {
         if !rules.double_after_split()
             && (pc == 2 || pc == 3)
             && (dealer_up_card == 2 || dealer_up_card == 3)
+            && !(single_deck && pc == 2 && dealer_up_card == 3)
         {
             return false;
         }
@@ -49,7 +75,7 @@ pub fn should_i_split(
 
     // 6
     if pc == 6 && dealer_up_card >= 2 && dealer_up_card <= 6 {
-        if !rules.double_after_split() && dealer_up_card == 2 {
+        if !rules.double_after_split() && dealer_up_card == 2 && !single_deck {
             return false;
         }
 
@@ -70,7 +96,7 @@ pub fn should_i_split(
 
 #[cfg(test)]
 mod tests {
-    use crate::types::Deviations;
+    use crate::types::{Deviations, RulesBuilder};
 
     use super::*;
 
@@ -79,39 +105,39 @@ mod tests {
         let rules = Rules::default();
 
         for duc in 2..=11 {
-            assert!(should_i_split(&vec![11, 11], duc, 0, &rules));
-            assert!(!should_i_split(&vec![10, 10], duc, 0, &rules));
+            assert!(should_i_split(&vec![11, 11], duc, 0, 0, &rules));
+            assert!(!should_i_split(&vec![10, 10], duc, 0, 0, &rules));
 
             if duc == 7 || duc == 10 || duc == 11 {
-                assert!(!should_i_split(&vec![9, 9], duc, 0, &rules));
+                assert!(!should_i_split(&vec![9, 9], duc, 0, 0, &rules));
             } else {
-                assert!(should_i_split(&vec![9, 9], duc, 0, &rules));
+                assert!(should_i_split(&vec![9, 9], duc, 0, 0, &rules));
             }
 
-            assert!(should_i_split(&vec![8, 8], duc, 0, &rules));
+            assert!(should_i_split(&vec![8, 8], duc, 0, 0, &rules));
 
             if duc > 7 {
-                assert!(!should_i_split(&vec![7, 7], duc, 0, &rules));
-                assert!(!should_i_split(&vec![2, 2], duc, 0, &rules));
-                assert!(!should_i_split(&vec![3, 3], duc, 0, &rules));
+                assert!(!should_i_split(&vec![7, 7], duc, 0, 0, &rules));
+                assert!(!should_i_split(&vec![2, 2], duc, 0, 0, &rules));
+                assert!(!should_i_split(&vec![3, 3], duc, 0, 0, &rules));
             } else {
-                assert!(should_i_split(&vec![7, 7], duc, 0, &rules));
-                assert!(should_i_split(&vec![2, 2], duc, 0, &rules));
-                assert!(should_i_split(&vec![3, 3], duc, 0, &rules));
+                assert!(should_i_split(&vec![7, 7], duc, 0, 0, &rules));
+                assert!(should_i_split(&vec![2, 2], duc, 0, 0, &rules));
+                assert!(should_i_split(&vec![3, 3], duc, 0, 0, &rules));
             }
 
             if duc > 6 {
-                assert!(!should_i_split(&vec![6, 6], duc, 0, &rules));
+                assert!(!should_i_split(&vec![6, 6], duc, 0, 0, &rules));
             } else {
-                assert!(should_i_split(&vec![6, 6], duc, 0, &rules));
+                assert!(should_i_split(&vec![6, 6], duc, 0, 0, &rules));
             }
 
-            assert!(!should_i_split(&vec![5, 5], duc, 0, &rules));
+            assert!(!should_i_split(&vec![5, 5], duc, 0, 0, &rules));
 
             if duc < 5 || duc > 6 {
-                assert!(!should_i_split(&vec![4, 4], duc, 0, &rules));
+                assert!(!should_i_split(&vec![4, 4], duc, 0, 0, &rules));
             } else {
-                assert!(should_i_split(&vec![4, 4], duc, 0, &rules));
+                assert!(should_i_split(&vec![4, 4], duc, 0, 0, &rules));
             }
         }
     }
@@ -126,25 +152,25 @@ mod tests {
         let tc = 3;
 
         for duc in 2..=11 {
-            assert!(!should_i_split(pc, duc, tc, &rules));
+            assert!(!should_i_split(pc, duc, 0, tc, &rules));
         }
 
         let tc = 4;
 
         for duc in 2..=11 {
             if duc == 6 {
-                assert!(should_i_split(pc, duc, tc, &rules));
+                assert!(should_i_split(pc, duc, 0, tc, &rules));
                 continue;
             }
 
-            assert!(!should_i_split(pc, duc, tc, &rules));
+            assert!(!should_i_split(pc, duc, 0, tc, &rules));
         }
 
         let tc = 5;
 
         for duc in 2..=11 {
             if duc == 6 || duc == 5 {
-                assert!(should_i_split(pc, duc, tc, &rules));
+                assert!(should_i_split(pc, duc, 0, tc, &rules));
                 continue;
             }
         }
@@ -153,11 +179,48 @@ mod tests {
 
         for duc in 2..=11 {
             if duc == 6 || duc == 5 || duc == 4 {
-                assert!(should_i_split(pc, duc, tc, &rules));
+                assert!(should_i_split(pc, duc, 0, tc, &rules));
                 continue;
             }
 
-            assert!(!should_i_split(pc, duc, tc, &rules));
+            assert!(!should_i_split(pc, duc, 0, tc, &rules));
         }
     }
+
+    #[test]
+    fn no_hole_card() {
+        let rules = RulesBuilder::new().no_hole_card(true).build();
+
+        assert!(!should_i_split(&vec![8, 8], 10, 0, 0, &rules));
+        assert!(!should_i_split(&vec![8, 8], 11, 0, 0, &rules));
+        assert!(!should_i_split(&vec![11, 11], 11, 0, 0, &rules));
+        assert!(should_i_split(&vec![11, 11], 10, 0, 0, &rules));
+        assert!(should_i_split(&vec![8, 8], 9, 0, 0, &rules));
+    }
+
+    #[test]
+    fn single_deck() {
+        let das = RulesBuilder::new()
+            .decks(1)
+            .double_after_split(true)
+            .build();
+        let ndas = RulesBuilder::new()
+            .decks(1)
+            .double_after_split(false)
+            .build();
+        let shoe = RulesBuilder::new()
+            .decks(6)
+            .double_after_split(true)
+            .build();
+
+        for (pair, duc) in [(3, 8), (7, 8), (6, 7), (4, 4)] {
+            assert!(should_i_split(&vec![pair, pair], duc, 0, 0, &das));
+            assert!(!should_i_split(&vec![pair, pair], duc, 0, 0, &ndas));
+            assert!(!should_i_split(&vec![pair, pair], duc, 0, 0, &shoe));
+        }
+
+        assert!(should_i_split(&vec![2, 2], 3, 0, 0, &ndas));
+        assert!(should_i_split(&vec![6, 6], 2, 0, 0, &ndas));
+        assert!(!should_i_split(&vec![3, 3], 3, 0, 0, &ndas));
+    }
 }
@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+
+use crate::risk_of_ruin::{lifetime_risk_of_ruin, trip_risk_of_ruin};
+
+const Z_95: f64 = 1.96;
+
+/// Mean and variance accumulated with Welford's algorithm. Two accumulators can be merged, which
+/// gives the same result as if every value had been pushed into one of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub fn merge(&mut self, other: &RunningStats) {
+        if other.count == 0 {
+            return;
+        }
+
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2 + delta * delta * (self.count * other.count) as f64 / count as f64;
+        self.count = count;
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample variance
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+
+        self.m2 / (self.count - 1) as f64
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn standard_error(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        self.std_dev() / (self.count as f64).sqrt()
+    }
+
+    /// 95% confidence interval of the mean
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = Z_95 * self.standard_error();
+
+        (self.mean - margin, self.mean + margin)
+    }
+}
+
+/// Per round results of a simulation, overall and bucketed by the true count at the start of the
+/// round. Results are in units.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    rounds: usize,
+    total_bet: f64,
+    results: RunningStats,
+    by_true_count: BTreeMap<isize, RunningStats>,
+}
+
+impl Statistics {
+    pub fn record(&mut self, true_count: isize, bet: f64, net: f64) {
+        self.rounds += 1;
+        self.total_bet += bet;
+        self.results.push(net);
+        self.by_true_count.entry(true_count).or_default().push(net);
+    }
+
+    /// Round the player sat out
+    pub fn record_sat_out(&mut self) {
+        self.rounds += 1;
+    }
+
+    pub fn merge(&mut self, other: &Statistics) {
+        self.rounds += other.rounds;
+        self.total_bet += other.total_bet;
+        self.results.merge(&other.results);
+
+        for (true_count, stats) in &other.by_true_count {
+            self.by_true_count
+                .entry(*true_count)
+                .or_default()
+                .merge(stats);
+        }
+    }
+
+    /// Rounds dealt, including the ones sat out
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn hands_played(&self) -> usize {
+        self.results.count()
+    }
+
+    /// Total units won or lost
+    pub fn net(&self) -> f64 {
+        self.results.mean() * self.results.count() as f64
+    }
+
+    pub fn results(&self) -> &RunningStats {
+        &self.results
+    }
+
+    pub fn by_true_count(&self) -> &BTreeMap<isize, RunningStats> {
+        &self.by_true_count
+    }
+
+    /// Average units won per hand played
+    pub fn win_rate(&self) -> f64 {
+        self.results.mean()
+    }
+
+    pub fn win_rate_per_100(&self) -> f64 {
+        self.win_rate() * 100.0
+    }
+
+    pub fn hourly_ev(&self, hands_per_hour: f64) -> f64 {
+        self.win_rate() * hands_per_hour
+    }
+
+    /// Standard deviation of a single hand played
+    pub fn std_dev(&self) -> f64 {
+        self.results.std_dev()
+    }
+
+    pub fn std_dev_per_100(&self) -> f64 {
+        self.std_dev() * 10.0
+    }
+
+    /// Average initial bet
+    pub fn average_bet(&self) -> f64 {
+        if self.hands_played() == 0 {
+            return 0.0;
+        }
+
+        self.total_bet / self.hands_played() as f64
+    }
+
+    /// Units won per unit of initial bet
+    pub fn edge(&self) -> f64 {
+        if self.total_bet == 0.0 {
+            return 0.0;
+        }
+
+        self.net() / self.total_bet
+    }
+
+    /// Hands needed for the expected win to equal one standard deviation
+    pub fn n0(&self) -> f64 {
+        if self.win_rate() == 0.0 {
+            return f64::INFINITY;
+        }
+
+        self.results.variance() / (self.win_rate() * self.win_rate())
+    }
+
+    /// Desirability Index, 1000 times the win rate divided by the standard deviation
+    pub fn desirability_index(&self) -> f64 {
+        if self.std_dev() == 0.0 {
+            return 0.0;
+        }
+
+        1000.0 * self.win_rate() / self.std_dev()
+    }
+
+    /// Win per 100 hands for a 10,000 unit bankroll at optimal bet sizing, the square of the
+    /// Desirability Index
+    pub fn score(&self) -> f64 {
+        if self.results.variance() == 0.0 {
+            return 0.0;
+        }
+
+        1_000_000.0 * self.win_rate() * self.win_rate() / self.results.variance()
+    }
+
+    /// 95% confidence interval of the win rate per hand
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        self.results.confidence_interval()
+    }
+
+    /// Analytic risk of ruin for a bankroll in units using this win rate and standard deviation,
+    /// lifetime when `hands` is `None`
+    pub fn risk_of_ruin(&self, bankroll: f64, hands: Option<usize>) -> f64 {
+        match hands {
+            Some(hands) => trip_risk_of_ruin(self.win_rate(), self.std_dev(), bankroll, hands),
+            None => lifetime_risk_of_ruin(self.win_rate(), self.std_dev(), bankroll),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn running_stats() {
+        let mut stats = RunningStats::default();
+
+        for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            stats.push(value);
+        }
+
+        assert_eq!(stats.count(), 8);
+        assert!(approx(stats.mean(), 5.0));
+        assert!(approx(stats.variance(), 32.0 / 7.0));
+    }
+
+    #[test]
+    fn stable_with_large_offsets() {
+        let mut stats = RunningStats::default();
+
+        for value in [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0] {
+            stats.push(value);
+        }
+
+        assert!(approx(stats.variance(), 30.0));
+    }
+
+    #[test]
+    fn merge_matches_single_pass() {
+        let values = [-1.0, 1.0, 1.5, -2.0, 0.0, 1.0, 1.0, -1.0, 2.0];
+
+        let mut single = RunningStats::default();
+        values.iter().for_each(|v| single.push(*v));
+
+        let mut a = RunningStats::default();
+        let mut b = RunningStats::default();
+        values[..4].iter().for_each(|v| a.push(*v));
+        values[4..].iter().for_each(|v| b.push(*v));
+        a.merge(&b);
+
+        assert_eq!(a.count(), single.count());
+        assert!(approx(a.mean(), single.mean()));
+        assert!(approx(a.variance(), single.variance()));
+
+        let mut empty = RunningStats::default();
+        empty.merge(&single);
+        assert_eq!(empty, single);
+    }
+
+    #[test]
+    fn statistics_metrics() {
+        let mut stats = Statistics::default();
+
+        for (tc, bet, net) in [(0, 1.0, 1.0), (0, 1.0, -1.0), (2, 4.0, 4.0), (2, 4.0, -2.0)] {
+            stats.record(tc, bet, net);
+        }
+        stats.record_sat_out();
+
+        assert_eq!(stats.rounds(), 5);
+        assert_eq!(stats.hands_played(), 4);
+        assert!(approx(stats.net(), 2.0));
+        assert!(approx(stats.win_rate(), 0.5));
+        assert!(approx(stats.win_rate_per_100(), 50.0));
+        assert!(approx(stats.hourly_ev(80.0), 40.0));
+        assert!(approx(stats.average_bet(), 2.5));
+        assert!(approx(stats.edge(), 0.2));
+
+        let variance = stats.results().variance();
+        assert!(approx(stats.std_dev_per_100(), variance.sqrt() * 10.0));
+        assert!(approx(stats.n0(), variance / 0.25));
+        assert!(approx(
+            stats.score(),
+            stats.desirability_index() * stats.desirability_index()
+        ));
+        assert!(approx(stats.n0() * stats.score(), 1_000_000.0));
+
+        let (low, high) = stats.confidence_interval();
+        assert!(low < 0.5 && high > 0.5);
+        assert!(approx(high - 0.5, 1.96 * stats.std_dev() / 2.0));
+
+        assert_eq!(stats.by_true_count().len(), 2);
+        assert!(approx(stats.by_true_count()[&0].mean(), 0.0));
+        assert!(approx(stats.by_true_count()[&2].mean(), 1.0));
+    }
+
+    #[test]
+    fn statistics_merge() {
+        let mut a = Statistics::default();
+        let mut b = Statistics::default();
+        let mut both = Statistics::default();
+
+        for (i, net) in [1.0, -1.0, 1.5, 0.0, -1.0, 2.0].iter().enumerate() {
+            let tc = i as isize % 3 - 1;
+
+            if i % 2 == 0 {
+                a.record(tc, 1.0, *net);
+            } else {
+                b.record(tc, 1.0, *net);
+            }
+            both.record(tc, 1.0, *net);
+        }
+        b.record_sat_out();
+        both.record_sat_out();
+
+        a.merge(&b);
+
+        assert_eq!(a.rounds(), both.rounds());
+        assert_eq!(a.hands_played(), both.hands_played());
+        assert!(approx(a.win_rate(), both.win_rate()));
+        assert!(approx(a.std_dev(), both.std_dev()));
+        assert_eq!(a.by_true_count().len(), both.by_true_count().len());
+    }
+}
@@ -1,119 +1,59 @@
-use crate::types::{GameType, Rules};
+use crate::{
+    counter::TrueCount,
+    index_plays::{Action, deviation},
+    types::Rules,
+};
 
 pub fn should_i_surrender(
     cards_in_hand: &Vec<u8>,
     dealer_up_card: u8,
     running_count: isize,
-    true_count: isize,
+    true_count: impl Into<TrueCount>,
     rules: &Rules,
 ) -> bool {
     if !rules.surrender() {
         return false;
     }
 
+    let (running_count, true_count) =
+        rules.effective_exact_counts(running_count, true_count.into());
+
     let total: u8 = cards_in_hand.iter().sum();
 
-    if cards_in_hand.len() > 2 || cards_in_hand.contains(&11) || (total < 14 || total > 17) {
-        return false;
+    // single deck, with two of the four sevens gone 7-7 rarely draws to 21
+    if rules.decks() == 1 && cards_in_hand[..] == [7, 7] && dealer_up_card == 10 {
+        return true;
     }
 
-    if total == 17
-        && rules.game_type() == &GameType::Hit17
-        && dealer_up_card == 11
-        && rules.enable_deviations() > 0
-    {
-        return true;
+    if cards_in_hand.len() > 2 || cards_in_hand.contains(&11) || (total < 14 || total > 17) {
+        return false;
     }
 
-    if total == 16 {
-        if dealer_up_card == 9 || dealer_up_card == 10 || dealer_up_card == 11 {
-            if rules.enable_deviations() > 0 {
-                if (dealer_up_card == 10 && cards_in_hand[0] == cards_in_hand[1] && true_count < 1)
-                    || (dealer_up_card == 9 && true_count <= -1)
-                    || (dealer_up_card == 9 && cards_in_hand[0] == cards_in_hand[1])
-                {
-                    return false;
-                }
-            }
-
-            if dealer_up_card == 11 {
-                if cards_in_hand[0] == cards_in_hand[1] {
-                    if rules.enable_deviations() > 0 {
-                        match rules.game_type() {
-                            GameType::Hit17 => return true,
-                            _ => (),
-                        };
-                    }
-
-                    return false;
-                }
-            }
-
-            if (dealer_up_card == 9 || dealer_up_card == 10) && rules.enable_deviations() == 0 {
-                if cards_in_hand[0] == cards_in_hand[1] {
-                    return false;
-                }
-            }
-
-            return true;
-        }
-
-        if dealer_up_card == 8 && true_count >= 4 && rules.enable_deviations() > 0 {
-            return true;
-        }
+    match deviation(
+        cards_in_hand,
+        dealer_up_card,
+        running_count,
+        true_count,
+        rules,
+        &[Action::Surrender, Action::NoSurrender],
+    ) {
+        Some(Action::Surrender) => return true,
+        Some(Action::NoSurrender) => return false,
+        _ => (),
     }
 
-    if total == 15 {
-        if dealer_up_card == 10 {
-            if running_count < 0 {
-                return false;
-            }
-
-            return true;
-        }
-
-        if rules.enable_deviations() > 0 {
-            if dealer_up_card == 9 && true_count >= 2 {
-                return true;
-            }
-
-            if dealer_up_card == 11 {
-                match rules.game_type() {
-                    GameType::Hit17 if true_count >= -1 => return true,
-                    GameType::Stand17 if true_count >= 2 => return true,
-                    _ => (),
-                }
-            }
-        }
+    // 8-8 is split instead, except against a ten in single deck
+    if total == 16 && dealer_up_card >= 9 {
+        return cards_in_hand[0] != cards_in_hand[1]
+            || (rules.decks() == 1 && dealer_up_card == 10);
     }
 
-    if total == 14 && rules.enable_deviations() > 1 {
-        match rules.game_type() {
-            GameType::Hit17 => {
-                if (true_count >= 6 && dealer_up_card == 9)
-                    || (true_count >= 4 && dealer_up_card == 10)
-                    || (true_count >= 4 && dealer_up_card == 11)
-                {
-                    return true;
-                }
-            }
-            GameType::Stand17 => {
-                if (true_count >= 7 && dealer_up_card == 9)
-                    || (true_count >= 4 && dealer_up_card == 10)
-                    || (true_count >= 6 && dealer_up_card == 11)
-                {
-                    return true;
-                }
-            }
-        }
-    };
-
-    false
+    total == 15 && dealer_up_card == 10
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::types::Deviations;
+    use crate::types::{Deviations, GameType, RulesBuilder};
 
     use super::*;
 
@@ -209,6 +149,8 @@ mod tests {
         assert!(!should_i_surrender(&vec![10, 6], 9, 10, -1, &rules));
 
         assert!(should_i_surrender(&vec![10, 6], 9, 10, 0, &rules));
+
+        assert!(should_i_surrender(&vec![10, 6], 9, 10, -0.5, &rules));
     }
 
     #[test]
@@ -327,4 +269,48 @@ mod tests {
 
         assert!(!should_i_surrender(&vec![8, 8], 10, 10, 6, &rules));
     }
+
+    #[test]
+    fn test_fab_4() {
+        let rules = RulesBuilder::new()
+            .surrender(true)
+            .enable_deviations(Deviations::Fab4)
+            .build();
+
+        assert!(should_i_surrender(&vec![10, 4], 10, 0, 3, &rules));
+        assert!(!should_i_surrender(&vec![10, 4], 10, 0, 2, &rules));
+        assert!(should_i_surrender(&vec![10, 5], 10, -5, 0, &rules));
+        assert!(!should_i_surrender(&vec![10, 5], 10, 5, -1, &rules));
+        // not part of the Fab 4
+        assert!(!should_i_surrender(&vec![8, 8], 10, 10, 1, &rules));
+        assert!(!should_i_surrender(&vec![10, 7], 11, 0, 0, &rules));
+    }
+
+    #[test]
+    fn test_15_v_10_basic_strategy() {
+        let rules = RulesBuilder::new().surrender(true).build();
+
+        assert!(should_i_surrender(&vec![10, 5], 10, -5, -1, &rules));
+    }
+
+    #[test]
+    fn test_single_deck_7s_v_10() {
+        let single = RulesBuilder::new().decks(1).surrender(true).build();
+        let shoe = RulesBuilder::new().decks(6).surrender(true).build();
+
+        assert!(should_i_surrender(&vec![7, 7], 10, 0, 0, &single));
+        assert!(!should_i_surrender(&vec![7, 7], 10, 0, 0, &shoe));
+        assert!(!should_i_surrender(&vec![7, 7], 9, 0, 0, &single));
+    }
+
+    #[test]
+    fn test_single_deck_8s_v_10() {
+        let single = RulesBuilder::new().decks(1).surrender(true).build();
+        let shoe = RulesBuilder::new().decks(6).surrender(true).build();
+
+        assert!(should_i_surrender(&vec![8, 8], 10, 0, 0, &single));
+        assert!(!should_i_surrender(&vec![8, 8], 10, 0, 0, &shoe));
+        assert!(!should_i_surrender(&vec![8, 8], 9, 0, 0, &single));
+        assert!(!should_i_surrender(&vec![8, 8], 11, 0, 0, &single));
+    }
 }
@@ -0,0 +1,478 @@
+use std::collections::BTreeMap;
+
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    counting_system::nominal_decks_remaining,
+    get_decision,
+    spaced_repetition::ReviewSchedule,
+    types::{Decision, Deviations, Rules},
+};
+
+/// Rank of each card in a deck, tens are four times as likely as any other card
+const DECK_RANKS: [u8; 13] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 10, 10, 10, 11];
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum HandWeighting {
+    /// Every starting hand and dealer up card is equally likely
+    #[default]
+    Uniform,
+    /// Cards are drawn as they would be from a shoe, so hands with tens come up most
+    GameFrequency,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Category {
+    Hard,
+    Soft,
+    Pair,
+    Surrender,
+    /// Hands where the count changes the basic strategy play
+    Deviation,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Question {
+    pub cards: Vec<u8>,
+    pub dealer_up_card: u8,
+    pub running_count: isize,
+    pub true_count: isize,
+}
+
+impl Question {
+    /// Two card starting hand against a dealer up card, the only hands the trainer deals
+    pub fn is_valid(&self) -> bool {
+        self.cards.len() == 2
+            && self.cards.iter().all(|card| (2..=11).contains(card))
+            && (2..=11).contains(&self.dealer_up_card)
+    }
+}
+
+/// Question that isn't a two card starting hand, it can't be graded
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidQuestion(pub Question);
+
+impl std::fmt::Display for InvalidQuestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid question {:?} against {}",
+            self.0.cards, self.0.dealer_up_card
+        )
+    }
+}
+
+impl std::error::Error for InvalidQuestion {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grade {
+    pub correct: bool,
+    pub expected: Decision,
+    pub category: Category,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Accuracy {
+    pub correct: usize,
+    pub total: usize,
+}
+
+impl Accuracy {
+    /// Fraction answered correctly, zero when nothing was answered yet
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        self.correct as f64 / self.total as f64
+    }
+
+    fn record(&mut self, correct: bool) {
+        self.total += 1;
+        self.correct += usize::from(correct);
+    }
+}
+
+/// Generates starting hands and grades the answers against `get_decision` for the rules. Missed
+/// hands are brought back by the review schedule before any new hand is dealt.
+#[derive(Clone, Debug)]
+pub struct Trainer {
+    rules: Rules,
+    basic_rules: Rules,
+    weighting: HandWeighting,
+    true_counts: (isize, isize),
+    results: BTreeMap<Category, Accuracy>,
+    review_schedule: ReviewSchedule,
+}
+
+impl Trainer {
+    pub fn new(rules: Rules) -> Self {
+        let mut basic_rules = rules.clone();
+        basic_rules.set_enable_deviations(Deviations::None);
+        basic_rules.set_custom_index_plays(vec![]);
+
+        Self {
+            rules,
+            basic_rules,
+            weighting: HandWeighting::default(),
+            true_counts: (0, 0),
+            results: BTreeMap::new(),
+            review_schedule: ReviewSchedule::default(),
+        }
+    }
+
+    pub fn weighting(mut self, val: HandWeighting) -> Self {
+        self.weighting = val;
+        self
+    }
+
+    /// Range of true counts questions are asked at, only matters when deviations are enabled
+    pub fn true_counts(mut self, min: isize, max: isize) -> Self {
+        self.true_counts = (min.min(max), max.max(min));
+        self
+    }
+
+    pub fn question<R: Rng + ?Sized>(&self, rng: &mut R) -> Question {
+        if let Some(question) = self.review_schedule.next_due() {
+            return question.clone();
+        }
+
+        let (cards, dealer_up_card) = match self.weighting {
+            HandWeighting::Uniform => uniform_hand(rng),
+            HandWeighting::GameFrequency => dealt_hand(rng),
+        };
+
+        let true_count =
+            rng.random_range(self.true_counts.0 as i64..=self.true_counts.1 as i64) as isize;
+
+        Question {
+            cards,
+            dealer_up_card,
+            running_count: running_count_for(&self.rules, true_count),
+            true_count,
+        }
+    }
+
+    /// Correct play and category of a question without recording anything
+    pub fn grade(&self, question: &Question, answer: Decision) -> Result<Grade, InvalidQuestion> {
+        if !question.is_valid() {
+            return Err(InvalidQuestion(question.clone()));
+        }
+
+        let expected = self.decision(question, &self.rules);
+
+        let category = if self.rules.has_index_plays()
+            && self.decision(question, &self.basic_rules) != expected
+        {
+            Category::Deviation
+        } else if expected == Decision::Surrender {
+            Category::Surrender
+        } else if question.cards[0] == question.cards[1] {
+            Category::Pair
+        } else if question.cards.contains(&11) {
+            Category::Soft
+        } else {
+            Category::Hard
+        };
+
+        Ok(Grade {
+            correct: answer == expected,
+            expected,
+            category,
+        })
+    }
+
+    /// Grades the answer and records it in the category's accuracy and the review schedule
+    pub fn answer(
+        &mut self,
+        question: &Question,
+        answer: Decision,
+    ) -> Result<Grade, InvalidQuestion> {
+        let grade = self.grade(question, answer)?;
+
+        self.review_schedule.record(question, grade.correct);
+
+        self.results
+            .entry(grade.category)
+            .or_default()
+            .record(grade.correct);
+
+        Ok(grade)
+    }
+
+    pub fn accuracy(&self, category: Category) -> Accuracy {
+        self.results.get(&category).copied().unwrap_or_default()
+    }
+
+    pub fn overall_accuracy(&self) -> Accuracy {
+        self.results
+            .values()
+            .fold(Accuracy::default(), |total, accuracy| Accuracy {
+                correct: total.correct + accuracy.correct,
+                total: total.total + accuracy.total,
+            })
+    }
+
+    pub fn results(&self) -> &BTreeMap<Category, Accuracy> {
+        &self.results
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn review_schedule(&self) -> &ReviewSchedule {
+        &self.review_schedule
+    }
+
+    /// Clears the accuracy, the review schedule is kept
+    pub fn reset(&mut self) {
+        self.results.clear();
+    }
+
+    /// Restores a schedule saved from an earlier session
+    pub fn set_review_schedule(&mut self, value: ReviewSchedule) {
+        self.review_schedule = value;
+    }
+
+    fn decision(&self, question: &Question, rules: &Rules) -> Decision {
+        get_decision(
+            &question.cards,
+            question.dealer_up_card,
+            question.running_count,
+            question.true_count,
+            rules,
+        )
+    }
+}
+
+fn is_blackjack(cards: &[u8]) -> bool {
+    cards.contains(&10) && cards.contains(&11)
+}
+
+fn uniform_hand<R: Rng + ?Sized>(rng: &mut R) -> (Vec<u8>, u8) {
+    let hands: Vec<[u8; 2]> = (2..=11)
+        .flat_map(|first| (first..=11).map(move |second| [first, second]))
+        .filter(|cards| !is_blackjack(cards))
+        .collect();
+
+    let cards = hands.choose(rng).copied().unwrap_or([10, 10]);
+
+    (cards.to_vec(), rng.random_range(2..=11))
+}
+
+fn dealt_hand<R: Rng + ?Sized>(rng: &mut R) -> (Vec<u8>, u8) {
+    loop {
+        let cards: Vec<u8> = (0..2)
+            .filter_map(|_| DECK_RANKS.choose(rng).copied())
+            .collect();
+
+        if !is_blackjack(&cards) {
+            return (cards, DECK_RANKS.choose(rng).copied().unwrap_or(10));
+        }
+    }
+}
+
+/// Running count that gives the true count with half of the shoe left, the same assumption
+/// `Rules::effective_counts` makes for unbalanced systems
+fn running_count_for(rules: &Rules, true_count: isize) -> isize {
+    let system = rules.counting_system().system();
+    let decks_remaining = nominal_decks_remaining(rules.decks());
+
+    system.pivot()
+        + ((true_count - system.imbalance_per_deck()) as f32 * decks_remaining).round() as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        index_plays::{Action, Comparison, Count, Hand, IndexPlay},
+        rng::seeded_rng,
+        types::{CountingSystemType, GameType, RulesBuilder},
+    };
+
+    use super::*;
+
+    fn question(cards: Vec<u8>, dealer_up_card: u8, true_count: isize, rules: &Rules) -> Question {
+        Question {
+            cards,
+            dealer_up_card,
+            running_count: running_count_for(rules, true_count),
+            true_count,
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_questions() {
+        let rules = Rules::default();
+        let mut trainer = Trainer::new(rules.clone());
+
+        for invalid in [
+            question(vec![10], 6, 0, &rules),
+            question(vec![10, 6, 2], 6, 0, &rules),
+            question(vec![10, 1], 6, 0, &rules),
+            question(vec![10, 6], 12, 0, &rules),
+        ] {
+            assert_eq!(
+                trainer.answer(&invalid, Decision::Hit),
+                Err(InvalidQuestion(invalid.clone()))
+            );
+        }
+
+        assert_eq!(trainer.overall_accuracy(), Accuracy::default());
+    }
+
+    #[test]
+    fn grades_against_the_charts() {
+        let rules = RulesBuilder::new().surrender(true).build();
+        let mut trainer = Trainer::new(rules.clone());
+
+        let grade = trainer
+            .answer(&question(vec![10, 6], 10, 0, &rules), Decision::Surrender)
+            .unwrap();
+        assert!(grade.correct);
+        assert_eq!(grade.category, Category::Surrender);
+
+        let grade = trainer
+            .answer(&question(vec![8, 8], 6, 0, &rules), Decision::Hit)
+            .unwrap();
+        assert!(!grade.correct);
+        assert_eq!(grade.expected, Decision::Split);
+        assert_eq!(grade.category, Category::Pair);
+
+        let grade = trainer
+            .answer(&question(vec![11, 7], 3, 0, &rules), Decision::Double)
+            .unwrap();
+        assert!(grade.correct);
+        assert_eq!(grade.category, Category::Soft);
+
+        let grade = trainer
+            .answer(&question(vec![5, 6], 10, 0, &rules), Decision::Double)
+            .unwrap();
+        assert!(grade.correct);
+        assert_eq!(grade.category, Category::Hard);
+
+        assert_eq!(
+            trainer.accuracy(Category::Pair),
+            Accuracy {
+                correct: 0,
+                total: 1
+            }
+        );
+        assert_eq!(trainer.accuracy(Category::Deviation), Accuracy::default());
+        assert_eq!(trainer.overall_accuracy().total, 4);
+        assert_eq!(trainer.overall_accuracy().ratio(), 0.75);
+    }
+
+    #[test]
+    fn deviations_are_their_own_category() {
+        let rules = RulesBuilder::new()
+            .enable_deviations(Deviations::Standard)
+            .build();
+        let mut trainer = Trainer::new(rules.clone());
+
+        // 16 v 10 stands at a positive count
+        let grade = trainer
+            .answer(&question(vec![10, 6], 10, 1, &rules), Decision::Stand)
+            .unwrap();
+        assert!(grade.correct);
+        assert_eq!(grade.category, Category::Deviation);
+
+        let grade = trainer
+            .answer(&question(vec![10, 6], 10, -1, &rules), Decision::Hit)
+            .unwrap();
+        assert!(grade.correct);
+        assert_eq!(grade.category, Category::Hard);
+    }
+
+    #[test]
+    fn custom_plays_are_deviations() {
+        let rules = RulesBuilder::new()
+            .custom_index_plays(vec![IndexPlay::new(
+                Hand::Hard(13),
+                2,
+                Action::Hit,
+                Count::True,
+                Comparison::AtMost,
+                0,
+            )])
+            .build();
+        let mut trainer = Trainer::new(rules.clone());
+
+        let grade = trainer
+            .answer(&question(vec![10, 3], 2, 0, &rules), Decision::Hit)
+            .unwrap();
+        assert!(grade.correct);
+        assert_eq!(grade.category, Category::Deviation);
+    }
+
+    #[test]
+    fn questions_are_valid_hands() {
+        let rules = RulesBuilder::new().build();
+        let mut rng = seeded_rng(1);
+
+        for weighting in [HandWeighting::Uniform, HandWeighting::GameFrequency] {
+            let trainer = Trainer::new(rules.clone())
+                .weighting(weighting)
+                .true_counts(-2, 3);
+
+            for _ in 0..500 {
+                let question = trainer.question(&mut rng);
+
+                assert_eq!(question.cards.len(), 2);
+                assert!(!is_blackjack(&question.cards));
+                assert!((2..=11).contains(&question.dealer_up_card));
+                assert!((-2..=3).contains(&question.true_count));
+                assert_eq!(question.running_count, question.true_count * 3);
+            }
+        }
+    }
+
+    #[test]
+    fn missed_hands_come_back() {
+        let rules = RulesBuilder::new().game_type(GameType::Hit17).build();
+        let mut trainer = Trainer::new(rules.clone());
+        let mut rng = seeded_rng(3);
+
+        let soft_19_v_6 = question(vec![8, 11], 6, 0, &rules);
+        let grade = trainer.answer(&soft_19_v_6, Decision::Stand).unwrap();
+        assert!(!grade.correct);
+        assert_eq!(grade.expected, Decision::Double);
+
+        let other = trainer.question(&mut rng);
+        trainer.answer(&other, Decision::Stand).unwrap();
+
+        assert_eq!(trainer.question(&mut rng), soft_19_v_6);
+
+        let saved = trainer.review_schedule().clone();
+        let mut restored = Trainer::new(rules);
+        restored.set_review_schedule(saved);
+        assert_eq!(restored.question(&mut rng), soft_19_v_6);
+    }
+
+    #[test]
+    fn game_frequency_favours_tens() {
+        let mut rng = seeded_rng(2);
+        let trainer =
+            Trainer::new(RulesBuilder::new().build()).weighting(HandWeighting::GameFrequency);
+
+        let tens = (0..2_000)
+            .filter(|_| trainer.question(&mut rng).dealer_up_card == 10)
+            .count();
+
+        // 4 in 13
+        assert!(tens > 500 && tens < 740);
+    }
+
+    #[test]
+    fn running_count_for_unbalanced_systems() {
+        let rules = RulesBuilder::new()
+            .counting_system(CountingSystemType::KnockOut)
+            .build();
+
+        let (_, true_count) = rules.effective_counts(running_count_for(&rules, 2), 0);
+
+        assert_eq!(true_count, 2);
+    }
+}
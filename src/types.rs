@@ -1,15 +1,100 @@
 use proc_macros::{Random, ValueAssigner};
-use rand::seq::IndexedRandom;
+use rand::{Rng, seq::IndexedRandom};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, PartialEq, Random, ValueAssigner)]
+use crate::{
+    counter::{TrueCount, TrueCountRounding},
+    counting_system::{
+        CountingSystem, HiLo, HiOptI, HiOptII, KnockOut, OmegaII, RedSeven, WongHalves, Zen,
+        nominal_decks_remaining,
+    },
+    index_plays::{self, IndexPlay},
+    rng::RandomWith,
+    shoe::CARDS_PER_DECK,
+};
+
+/// Implements `RandomWith` by picking uniformly from the listed variants
+macro_rules! random_with {
+    ($type:ident, $($variant:ident),+ $(,)?) => {
+        impl RandomWith for $type {
+            fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+                [$($type::$variant),+]
+                    .choose(rng)
+                    .cloned()
+                    .unwrap_or_default()
+            }
+        }
+    };
+}
+
+random_with!(GameType, Hit17, Stand17);
+random_with!(SplitAces, No, SplitAcesOnce, ReSplitAces);
+random_with!(
+    IsDoubleAllowed,
+    No,
+    TenAndElevenOnly,
+    NineAndTenOnly,
+    NineTenAndAceOnly,
+    Any
+);
+random_with!(
+    PlayVariation,
+    PlayEverything,
+    AvoidPlayingNegativeTrueCounts,
+    PlayAboveNegativeTrueTwo,
+    PlayOnlyPositiveTrueCounts,
+    PlayAnyPositiveRunningCount
+);
+random_with!(
+    DeckPen,
+    Quater,
+    Half,
+    ThreeFourth,
+    One,
+    OneQuater,
+    OneAndHalf,
+    OneThreeFourth,
+    Two
+);
+random_with!(
+    Deviations,
+    None,
+    Standard,
+    Extended1,
+    Extended2,
+    Extended3,
+    Extended4,
+    Illustrious18,
+    Fab4,
+    Illustrious18AndFab4,
+    Full
+);
+random_with!(
+    OtherPlayersPlayType,
+    PerfectBasicStrategy,
+    Random,
+    NoBustStrategy
+);
+random_with!(
+    CountingSystemType,
+    HiLo,
+    KnockOut,
+    RedSeven,
+    HiOptI,
+    HiOptII,
+    OmegaII,
+    Zen,
+    WongHalves
+);
+
+#[derive(Clone, Debug, Default, PartialEq, Random, Serialize, Deserialize, ValueAssigner)]
 pub enum GameType {
     #[default]
     Hit17,
     Stand17,
 }
 
-#[derive(Clone, Debug, Default, Random, ValueAssigner)]
+#[derive(Clone, Debug, Default, PartialEq, Random, Serialize, Deserialize, ValueAssigner)]
 pub enum SplitAces {
     No,
     #[default]
@@ -17,7 +102,41 @@ pub enum SplitAces {
     ReSplitAces,
 }
 
-#[derive(Clone, Debug, Default, Random, ValueAssigner)]
+/// Payout of `numerator` to `denominator`, 3:2 is `Ratio::new(3, 2)`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Ratio {
+    pub numerator: u16,
+    pub denominator: u16,
+}
+
+impl Ratio {
+    pub const fn new(numerator: u16, denominator: u16) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Units won per unit bet, zero for a zero denominator which `try_build` rejects
+    pub fn value(&self) -> f64 {
+        if self.denominator == 0 {
+            return 0.0;
+        }
+
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl std::fmt::Display for Ratio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.numerator, self.denominator)
+    }
+}
+
+/// custom pays `payout` on a blackjack and `suited` on a blackjack of a single suit. `Custom`
+/// carries data, so unlike the other rule enums this one doesn't derive `Random` and
+/// `ValueAssigner`, pick from `BlackjackPayout::NAMED` or use `RandomWith` instead.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum BlackjackPayout {
     #[default]
     ThreeToTwo,
@@ -25,11 +144,112 @@ pub enum BlackjackPayout {
     SixToFiveSuitedTwoToOne,
     TwoToOne,
     Even,
+    Custom {
+        payout: Ratio,
+        suited: Ratio,
+    },
+}
+
+impl BlackjackPayout {
+    /// Every payout but `Custom`
+    pub const NAMED: [BlackjackPayout; 5] = [
+        BlackjackPayout::ThreeToTwo,
+        BlackjackPayout::SixToFive,
+        BlackjackPayout::SixToFiveSuitedTwoToOne,
+        BlackjackPayout::TwoToOne,
+        BlackjackPayout::Even,
+    ];
+
+    /// Named payout when there is one for the ratios, `Custom` otherwise
+    pub fn from_ratios(payout: Ratio, suited: Ratio) -> Self {
+        BlackjackPayout::NAMED
+            .into_iter()
+            .find(|named| named.ratio() == payout && named.suited_ratio() == suited)
+            .unwrap_or(BlackjackPayout::Custom { payout, suited })
+    }
+
+    pub fn ratio(&self) -> Ratio {
+        match self {
+            BlackjackPayout::ThreeToTwo => Ratio::new(3, 2),
+            BlackjackPayout::SixToFive | BlackjackPayout::SixToFiveSuitedTwoToOne => {
+                Ratio::new(6, 5)
+            }
+            BlackjackPayout::TwoToOne => Ratio::new(2, 1),
+            BlackjackPayout::Even => Ratio::new(1, 1),
+            BlackjackPayout::Custom { payout, .. } => *payout,
+        }
+    }
+
+    pub fn suited_ratio(&self) -> Ratio {
+        match self {
+            BlackjackPayout::SixToFiveSuitedTwoToOne => Ratio::new(2, 1),
+            BlackjackPayout::Custom { suited, .. } => *suited,
+            _ => self.ratio(),
+        }
+    }
+}
+
+impl RandomWith for BlackjackPayout {
+    fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        BlackjackPayout::NAMED
+            .choose(rng)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Payout of a three card bonus, `suited` is paid when the cards are of a single suit and
+/// defaults to `mixed`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bonus {
+    pub mixed: Option<Ratio>,
+    pub suited: Option<Ratio>,
+}
+
+impl Bonus {
+    pub fn ratio(&self, suited: bool) -> Option<Ratio> {
+        if suited {
+            self.suited.or(self.mixed)
+        } else {
+            self.mixed
+        }
+    }
+}
+
+/// Bonuses paid on 21s of three or more cards instead of the normal win, whatever the dealer
+/// has. They are only paid on hands that weren't doubled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bonuses {
+    pub six_seven_eight: Bonus,
+    pub seven_seven_seven: Bonus,
+    /// 21 of any three or more cards of a single suit
+    pub suited_21: Option<Ratio>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Random, ValueAssigner)]
+impl Bonuses {
+    pub fn any(&self) -> bool {
+        self != &Bonuses::default()
+    }
+
+    fn ratios(&self) -> impl Iterator<Item = Ratio> {
+        [
+            self.six_seven_eight.mixed,
+            self.six_seven_eight.suited,
+            self.seven_seven_seven.mixed,
+            self.seven_seven_seven.suited,
+            self.suited_21,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Random, Serialize, Deserialize, ValueAssigner)]
 pub enum IsDoubleAllowed {
     No,
+    TenAndElevenOnly,
     NineAndTenOnly,
     NineTenAndAceOnly,
     #[default]
@@ -62,13 +282,18 @@ impl IsDoubleAllowed {
 
     pub fn double_on_ten(&self) -> bool {
         match self {
-            IsDoubleAllowed::NineAndTenOnly
+            IsDoubleAllowed::TenAndElevenOnly
+            | IsDoubleAllowed::NineAndTenOnly
             | IsDoubleAllowed::NineTenAndAceOnly
             | IsDoubleAllowed::Any => true,
             _ => false,
         }
     }
 
+    pub fn double_on_eleven(&self) -> bool {
+        !self.no()
+    }
+
     pub fn double_on_ace(&self) -> bool {
         match self {
             IsDoubleAllowed::NineTenAndAceOnly | IsDoubleAllowed::Any => true,
@@ -77,7 +302,7 @@ impl IsDoubleAllowed {
     }
 }
 
-#[derive(Clone, Debug, Default, Random, ValueAssigner)]
+#[derive(Clone, Debug, Default, PartialEq, Random, Serialize, Deserialize, ValueAssigner)]
 pub enum PlayVariation {
     #[default]
     PlayEverything,
@@ -101,7 +326,7 @@ impl PlayVariation {
 }
 
 /// custom expects deck pen in decimal. Example: 0.5 means deck (26 cards)
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum DeckPen {
     Quater,
     Half,
@@ -131,7 +356,9 @@ impl DeckPen {
     }
 }
 
-#[derive(Clone, Debug, Default, Random, ValueAssigner)]
+/// Index plays made instead of basic strategy, see `index_plays` for the plays of each set. Plays
+/// can also be picked one by one with `RulesBuilder::custom_index_plays`.
+#[derive(Clone, Debug, Default, PartialEq, Random, Serialize, Deserialize, ValueAssigner)]
 pub enum Deviations {
     #[default]
     None,
@@ -140,6 +367,11 @@ pub enum Deviations {
     Extended2,
     Extended3,
     Extended4,
+    Illustrious18,
+    Fab4,
+    Illustrious18AndFab4,
+    /// Full Hi-Lo index set for shoe games
+    Full,
 }
 
 impl Deviations {
@@ -151,11 +383,33 @@ impl Deviations {
             Deviations::Extended2 => 3,
             Deviations::Extended3 => 4,
             Deviations::Extended4 => 5,
+            _ => 1,
         }
     }
+
+    pub fn index_plays(&self) -> &[IndexPlay] {
+        match self {
+            Deviations::None => &[],
+            Deviations::Standard => index_plays::STANDARD,
+            Deviations::Extended1
+            | Deviations::Extended2
+            | Deviations::Extended3
+            | Deviations::Extended4 => index_plays::EXTENDED,
+            Deviations::Illustrious18 => index_plays::ILLUSTRIOUS_18,
+            Deviations::Fab4 => index_plays::FAB_4,
+            Deviations::Illustrious18AndFab4 => index_plays::ILLUSTRIOUS_18_AND_FAB_4,
+            Deviations::Full => index_plays::FULL,
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        !self.index_plays().is_empty()
+    }
 }
 
-#[derive(Clone, Copy, Debug, Default, Random, Eq, PartialEq, ValueAssigner)]
+#[derive(
+    Clone, Copy, Debug, Default, Random, Eq, PartialEq, Serialize, Deserialize, ValueAssigner,
+)]
 pub enum OtherPlayersPlayType {
     #[default]
     PerfectBasicStrategy,
@@ -163,7 +417,40 @@ pub enum OtherPlayersPlayType {
     NoBustStrategy,
 }
 
-#[derive(Clone, Debug)]
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Random, Serialize, Deserialize, ValueAssigner,
+)]
+pub enum CountingSystemType {
+    #[default]
+    HiLo,
+    KnockOut,
+    RedSeven,
+    HiOptI,
+    HiOptII,
+    OmegaII,
+    Zen,
+    WongHalves,
+}
+
+impl CountingSystemType {
+    pub fn system(&self) -> &'static dyn CountingSystem {
+        match self {
+            CountingSystemType::HiLo => &HiLo,
+            CountingSystemType::KnockOut => &KnockOut,
+            CountingSystemType::RedSeven => &RedSeven,
+            CountingSystemType::HiOptI => &HiOptI,
+            CountingSystemType::HiOptII => &HiOptII,
+            CountingSystemType::OmegaII => &OmegaII,
+            CountingSystemType::Zen => &Zen,
+            CountingSystemType::WongHalves => &WongHalves,
+        }
+    }
+}
+
+/// Serialized as a `RulesSchema`, so stored profiles carry the schema version they were written
+/// with
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(into = "RulesSchema", try_from = "RulesSchema")]
 pub struct Rules {
     game_type: GameType,
     double_after_split: bool,
@@ -178,6 +465,10 @@ pub struct Rules {
     enable_deviations: Deviations,
     play_variation: PlayVariation,
     do_other_players_play_perfect_strategy: OtherPlayersPlayType,
+    counting_system: CountingSystemType,
+    no_hole_card: bool,
+    bonuses: Bonuses,
+    custom_index_plays: Vec<IndexPlay>,
 }
 
 impl Default for Rules {
@@ -196,6 +487,10 @@ impl Default for Rules {
             enable_deviations: Default::default(),
             play_variation: Default::default(),
             do_other_players_play_perfect_strategy: Default::default(),
+            counting_system: Default::default(),
+            no_hole_card: false,
+            bonuses: Bonuses::default(),
+            custom_index_plays: vec![],
         }
     }
 }
@@ -242,10 +537,15 @@ impl Rules {
         self.number_of_other_players
     }
 
+    /// `Standard` is 1 and `Extended1` to `Extended4` are 2 to 5, any other set is 1
     pub fn enable_deviations(&self) -> u8 {
         self.enable_deviations.get_val()
     }
 
+    pub fn deviations(&self) -> &Deviations {
+        &self.enable_deviations
+    }
+
     pub fn play_variation(&self) -> &PlayVariation {
         &self.play_variation
     }
@@ -253,6 +553,86 @@ impl Rules {
     pub fn do_other_players_play_perfect_strategy(&self) -> OtherPlayersPlayType {
         self.do_other_players_play_perfect_strategy
     }
+
+    pub fn counting_system(&self) -> CountingSystemType {
+        self.counting_system
+    }
+
+    /// European style game, the dealer takes the second card after the players have acted and
+    /// doubled or split bets are lost to a dealer blackjack
+    pub fn no_hole_card(&self) -> bool {
+        self.no_hole_card
+    }
+
+    pub fn bonuses(&self) -> &Bonuses {
+        &self.bonuses
+    }
+
+    /// Index plays supplied by the player. Where one is for the same hand, dealer up card and action
+    /// as plays of `deviations`, those are ignored whatever the count.
+    pub fn custom_index_plays(&self) -> &[IndexPlay] {
+        &self.custom_index_plays
+    }
+
+    /// Whether any index play, built-in or custom, can change a decision
+    pub fn has_index_plays(&self) -> bool {
+        self.enable_deviations.any() || !self.custom_index_plays.is_empty()
+    }
+
+    /// Hi-Lo index converted to the configured counting system
+    pub fn index(&self, hi_lo_index: isize) -> isize {
+        self.counting_system.system().index(hi_lo_index)
+    }
+
+    pub fn initial_running_count(&self) -> isize {
+        self.counting_system
+            .system()
+            .initial_running_count(self.decks)
+    }
+
+    pub fn key_count(&self) -> isize {
+        self.counting_system.system().key_count(self.decks)
+    }
+
+    pub fn pivot(&self) -> isize {
+        self.counting_system.system().pivot()
+    }
+
+    /// Running and true count as used by the chart functions. Balanced systems are passed through
+    /// as is. Unbalanced systems have no true count, so both are derived from the running count
+    /// relative to the pivot assuming half of the shoe is left to be dealt.
+    pub fn effective_counts(&self, running_count: isize, true_count: isize) -> (isize, isize) {
+        let (running_count, true_count) =
+            self.effective_exact_counts(running_count, true_count.into());
+
+        (running_count, true_count.rounded(TrueCountRounding::Floor))
+    }
+
+    /// `effective_counts` keeping the fraction of the true count
+    pub fn effective_exact_counts(
+        &self,
+        running_count: isize,
+        true_count: TrueCount,
+    ) -> (isize, TrueCount) {
+        let system = self.counting_system.system();
+
+        if system.is_balanced() {
+            return (running_count, true_count);
+        }
+
+        let decks_remaining = nominal_decks_remaining(self.decks);
+
+        (
+            system.balanced_running_count(running_count, decks_remaining),
+            system.true_count(running_count, decks_remaining).into(),
+        )
+    }
+
+    /// Same as `effective_counts` for decisions that only take a single count. Players using an
+    /// unbalanced system pass their running count.
+    pub fn effective_true_count(&self, count: isize) -> isize {
+        self.effective_counts(count, count).1
+    }
 }
 
 // setters
@@ -264,9 +644,17 @@ impl Rules {
     pub fn set_game_type(&mut self, game_type: GameType) {
         self.game_type = game_type;
     }
+
+    pub fn set_counting_system(&mut self, counting_system: CountingSystemType) {
+        self.counting_system = counting_system;
+    }
+
+    pub fn set_custom_index_plays(&mut self, value: Vec<IndexPlay>) {
+        self.custom_index_plays = value;
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct RulesBuilder {
     game_type: GameType,
     double_after_split: bool,
@@ -281,6 +669,10 @@ pub struct RulesBuilder {
     enable_deviations: Deviations,
     play_variation: PlayVariation,
     do_other_players_play_perfect_strategy: OtherPlayersPlayType,
+    counting_system: CountingSystemType,
+    no_hole_card: bool,
+    bonuses: Bonuses,
+    custom_index_plays: Vec<IndexPlay>,
 }
 
 impl RulesBuilder {
@@ -299,6 +691,10 @@ impl RulesBuilder {
             enable_deviations: Deviations::None,
             play_variation: PlayVariation::default(),
             do_other_players_play_perfect_strategy: Default::default(),
+            counting_system: Default::default(),
+            no_hole_card: false,
+            bonuses: Bonuses::default(),
+            custom_index_plays: vec![],
         }
     }
 
@@ -322,8 +718,8 @@ impl RulesBuilder {
         self
     }
 
-    pub fn decks(mut self, mut val: u8) -> Self {
-        val = val.clamp(2, 8);
+    /// Clamped to 1 to 8 by `build`
+    pub fn decks(mut self, val: u8) -> Self {
         self.decks = val;
         self
     }
@@ -338,8 +734,8 @@ impl RulesBuilder {
         self
     }
 
-    pub fn max_splits_allowed(mut self, mut val: u8) -> Self {
-        val = val.clamp(0, 3);
+    /// Clamped to at most 3 by `build`
+    pub fn max_splits_allowed(mut self, val: u8) -> Self {
         self.max_splits_allowed = val;
         self
     }
@@ -349,8 +745,8 @@ impl RulesBuilder {
         self
     }
 
-    pub fn number_of_other_players(mut self, mut val: u8) -> Self {
-        val = val.clamp(0, 4);
+    /// Clamped to at most 4 by `build`
+    pub fn number_of_other_players(mut self, val: u8) -> Self {
         self.number_of_other_players = val;
         self
     }
@@ -370,25 +766,426 @@ impl RulesBuilder {
         self
     }
 
+    pub fn counting_system(mut self, val: CountingSystemType) -> Self {
+        self.counting_system = val;
+        self
+    }
+
+    pub fn no_hole_card(mut self, val: bool) -> Self {
+        self.no_hole_card = val;
+        self
+    }
+
+    pub fn bonuses(mut self, val: Bonuses) -> Self {
+        self.bonuses = val;
+        self
+    }
+
+    /// Index plays applied on top of `enable_deviations`, see `Rules::custom_index_plays`
+    pub fn custom_index_plays(mut self, val: Vec<IndexPlay>) -> Self {
+        self.custom_index_plays = val;
+        self
+    }
+
+    /// Every rule drawn from `rng`, the drawn rules always pass `validate`. Aces aren't split when
+    /// no split is allowed and the deck penetration is redrawn until it fits in the shoe.
+    pub fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let game_type = GameType::random_with(rng);
+        let double_after_split = rng.random();
+        let split_aces = SplitAces::random_with(rng);
+        let surrender = rng.random();
+        let decks = rng.random_range(1..=8);
+        let blackjack_payout = BlackjackPayout::random_with(rng);
+        let is_double_allowed = IsDoubleAllowed::random_with(rng);
+        let max_splits_allowed = rng.random_range(0..=3);
+        let deck_pen = loop {
+            let deck_pen = DeckPen::random_with(rng);
+
+            if deck_pen.value() <= decks as usize * CARDS_PER_DECK {
+                break deck_pen;
+            }
+        };
+
+        Self::new()
+            .game_type(game_type)
+            .double_after_split(double_after_split)
+            .split_aces(if max_splits_allowed == 0 {
+                SplitAces::No
+            } else {
+                split_aces
+            })
+            .surrender(surrender)
+            .decks(decks)
+            .blackjack_payout(blackjack_payout)
+            .is_double_allowed(is_double_allowed)
+            .max_splits_allowed(max_splits_allowed)
+            .deck_pen(deck_pen)
+            .number_of_other_players(rng.random_range(0..=4))
+            .enable_deviations(Deviations::random_with(rng))
+            .play_variation(PlayVariation::random_with(rng))
+            .do_other_players_play_perfect_strategy(OtherPlayersPlayType::random_with(rng))
+            .counting_system(CountingSystemType::random_with(rng))
+            .no_hole_card(rng.random())
+    }
+
+    /// Every problem `build` would otherwise clamp or accept as is
+    pub fn validate(&self) -> Vec<RulesError> {
+        let mut errors = vec![];
+
+        if !(MIN_DECKS..=MAX_DECKS).contains(&self.decks) {
+            errors.push(RulesError::DecksOutOfRange(self.decks));
+        }
+
+        if self.max_splits_allowed > MAX_SPLITS {
+            errors.push(RulesError::TooManySplits(self.max_splits_allowed));
+        }
+
+        if self.number_of_other_players > MAX_OTHER_PLAYERS {
+            errors.push(RulesError::TooManyOtherPlayers(
+                self.number_of_other_players,
+            ));
+        }
+
+        if let DeckPen::Custom(decks) = self.deck_pen
+            && (!decks.is_finite() || decks < 0.0)
+        {
+            errors.push(RulesError::InvalidDeckPen(decks));
+        }
+
+        let total_cards = self.decks as usize * CARDS_PER_DECK;
+        if self.deck_pen.value() > total_cards {
+            errors.push(RulesError::DeckPenLargerThanShoe {
+                cards_behind_cut: self.deck_pen.value(),
+                total_cards,
+            });
+        }
+
+        let payout = &self.blackjack_payout;
+        for ratio in [payout.ratio(), payout.suited_ratio()]
+            .into_iter()
+            .chain(self.bonuses.ratios())
+        {
+            if ratio.denominator == 0 {
+                errors.push(RulesError::InvalidPayout(ratio));
+            }
+        }
+
+        if self.max_splits_allowed == 0 && !matches!(self.split_aces, SplitAces::No) {
+            errors.push(RulesError::SplitAcesWithoutSplits(self.split_aces.clone()));
+        }
+
+        for play in self
+            .enable_deviations
+            .index_plays()
+            .iter()
+            .chain(&self.custom_index_plays)
+        {
+            if !play.is_valid() {
+                errors.push(RulesError::InvalidIndexPlay(play.clone()));
+            }
+        }
+
+        errors
+    }
+
+    /// Same as `build` but rejects the configuration instead of clamping it
+    pub fn try_build(self) -> Result<Rules, Vec<RulesError>> {
+        let errors = self.validate();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(self.build())
+    }
+
     pub fn build(self) -> Rules {
         Rules {
             game_type: self.game_type,
             double_after_split: self.double_after_split,
             split_aces: self.split_aces,
             surrender: self.surrender,
-            decks: self.decks,
+            decks: self.decks.clamp(MIN_DECKS, MAX_DECKS),
             blackjack_payout: self.blackjack_payout,
             is_double_allowed: self.is_double_allowed,
-            max_splits_allowed: self.max_splits_allowed,
+            max_splits_allowed: self.max_splits_allowed.min(MAX_SPLITS),
             deck_pen: self.deck_pen,
-            number_of_other_players: self.number_of_other_players,
+            number_of_other_players: self.number_of_other_players.min(MAX_OTHER_PLAYERS),
             enable_deviations: self.enable_deviations,
             play_variation: self.play_variation,
             do_other_players_play_perfect_strategy: self.do_other_players_play_perfect_strategy,
+            counting_system: self.counting_system,
+            no_hole_card: self.no_hole_card,
+            bonuses: self.bonuses,
+            custom_index_plays: self.custom_index_plays,
+        }
+    }
+}
+
+// presets
+impl RulesBuilder {
+    /// 6D S17 DAS LS, 3:2, split aces once, 75% penetration
+    pub fn vegas_strip() -> Self {
+        Self::new()
+            .decks(6)
+            .game_type(GameType::Stand17)
+            .double_after_split(true)
+            .surrender(true)
+            .split_aces(SplitAces::SplitAcesOnce)
+            .deck_pen(DeckPen::OneAndHalf)
+    }
+
+    /// 2D H17 DAS, 3:2, no surrender
+    pub fn downtown() -> Self {
+        Self::new()
+            .decks(2)
+            .game_type(GameType::Hit17)
+            .double_after_split(true)
+            .surrender(false)
+            .deck_pen(DeckPen::Half)
+    }
+
+    /// 8D S17 DAS LS, 3:2
+    pub fn atlantic_city() -> Self {
+        Self::new()
+            .decks(8)
+            .game_type(GameType::Stand17)
+            .double_after_split(true)
+            .surrender(true)
+            .deck_pen(DeckPen::OneAndHalf)
+    }
+
+    /// 6D H17, doubling on 10 and 11 only, no double after split
+    pub fn reno() -> Self {
+        Self::new()
+            .decks(6)
+            .game_type(GameType::Hit17)
+            .double_after_split(false)
+            .surrender(false)
+            .is_double_allowed(IsDoubleAllowed::TenAndElevenOnly)
+            .deck_pen(DeckPen::OneAndHalf)
+    }
+
+    /// 6D S17 ENHC, doubling on 9 to 11, split once, no surrender
+    pub fn european() -> Self {
+        Self::new()
+            .decks(6)
+            .game_type(GameType::Stand17)
+            .no_hole_card(true)
+            .double_after_split(true)
+            .surrender(false)
+            .is_double_allowed(IsDoubleAllowed::NineAndTenOnly)
+            .split_aces(SplitAces::SplitAcesOnce)
+            .max_splits_allowed(1)
+            .deck_pen(DeckPen::OneAndHalf)
+    }
+
+    /// Pitch game paying 6:5 on a blackjack, H17 and no double after split
+    pub fn single_deck_six_to_five() -> Self {
+        Self::new()
+            .decks(1)
+            .game_type(GameType::Hit17)
+            .blackjack_payout(BlackjackPayout::SixToFive)
+            .double_after_split(false)
+            .surrender(false)
+            .max_splits_allowed(1)
+            .deck_pen(DeckPen::Half)
+    }
+
+    /// 6D S17 ENHC with DAS and late surrender
+    pub fn macau() -> Self {
+        Self::new()
+            .decks(6)
+            .game_type(GameType::Stand17)
+            .no_hole_card(true)
+            .double_after_split(true)
+            .surrender(true)
+            .deck_pen(DeckPen::OneAndHalf)
+    }
+
+    /// 8D S17 DAS shuffled after every round, so counting is of no use
+    pub fn online() -> Self {
+        Self::new()
+            .decks(8)
+            .game_type(GameType::Stand17)
+            .double_after_split(true)
+            .surrender(false)
+            .deck_pen(DeckPen::Custom(8.0))
+    }
+}
+
+const MIN_DECKS: u8 = 1;
+const MAX_DECKS: u8 = 8;
+const MAX_SPLITS: u8 = 3;
+const MAX_OTHER_PLAYERS: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RulesError {
+    DecksOutOfRange(u8),
+    TooManySplits(u8),
+    TooManyOtherPlayers(u8),
+    /// `DeckPen::Custom` that is negative or not a number
+    InvalidDeckPen(f32),
+    DeckPenLargerThanShoe {
+        cards_behind_cut: usize,
+        total_cards: usize,
+    },
+    /// Splitting aces is allowed while no split is
+    SplitAcesWithoutSplits(SplitAces),
+    /// Blackjack or bonus payout with a zero denominator
+    InvalidPayout(Ratio),
+    /// Index play for a hand that can't be dealt or an action that can't be taken on it
+    InvalidIndexPlay(IndexPlay),
+    UnsupportedSchemaVersion(u32),
+    /// Serialized rules without a schema version
+    MissingSchemaVersion,
+    /// Token of the rules shorthand that isn't recognized
+    UnknownShorthand(String),
+    /// Token setting a rule an earlier token already set
+    DuplicateShorthand(String),
+    /// Known rule the game can't model, such as early surrender
+    UnsupportedShorthand(String),
+}
+
+impl std::fmt::Display for RulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesError::DecksOutOfRange(decks) => {
+                write!(f, "{decks} decks, expected {MIN_DECKS} to {MAX_DECKS}")
+            }
+            RulesError::TooManySplits(splits) => {
+                write!(f, "{splits} splits allowed, expected at most {MAX_SPLITS}")
+            }
+            RulesError::TooManyOtherPlayers(players) => write!(
+                f,
+                "{players} other players, expected at most {MAX_OTHER_PLAYERS}"
+            ),
+            RulesError::InvalidDeckPen(decks) => write!(f, "invalid deck penetration {decks}"),
+            RulesError::DeckPenLargerThanShoe {
+                cards_behind_cut,
+                total_cards,
+            } => write!(
+                f,
+                "{cards_behind_cut} cards behind the cut card in a shoe of {total_cards}"
+            ),
+            RulesError::SplitAcesWithoutSplits(split_aces) => {
+                write!(f, "{split_aces:?} with no splits allowed")
+            }
+            RulesError::InvalidPayout(ratio) => write!(f, "invalid payout {ratio}"),
+            RulesError::InvalidIndexPlay(play) => write!(f, "invalid index play {play:?}"),
+            RulesError::MissingSchemaVersion => write!(f, "missing rules schema version"),
+            RulesError::UnsupportedSchemaVersion(version) => write!(
+                f,
+                "unsupported rules schema version {version}, expected at most {RULES_SCHEMA_VERSION}"
+            ),
+            RulesError::UnknownShorthand(token) => write!(f, "unknown rule {token:?}"),
+            RulesError::DuplicateShorthand(token) => {
+                write!(f, "{token:?} sets a rule that was already set")
+            }
+            RulesError::UnsupportedShorthand(token) => write!(f, "{token:?} is not supported"),
         }
     }
 }
 
+impl std::error::Error for RulesError {}
+
+/// Version written by `RulesSchema`, bump it whenever the fields change. Every older version is
+/// still read, version 2 added `custom_index_plays`.
+pub const RULES_SCHEMA_VERSION: u32 = 2;
+
+/// Stable serialized form of `Rules`. Only `version` is required, missing fields take their
+/// `Rules::default()` value. Values are validated like `RulesBuilder::try_build` does.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesSchema {
+    /// Zero when the field is missing, no version zero was ever written
+    #[serde(default)]
+    pub version: u32,
+    pub game_type: GameType,
+    pub double_after_split: bool,
+    pub split_aces: SplitAces,
+    pub surrender: bool,
+    pub decks: u8,
+    pub blackjack_payout: BlackjackPayout,
+    pub is_double_allowed: IsDoubleAllowed,
+    pub max_splits_allowed: u8,
+    pub deck_pen: DeckPen,
+    pub number_of_other_players: u8,
+    pub enable_deviations: Deviations,
+    pub play_variation: PlayVariation,
+    pub do_other_players_play_perfect_strategy: OtherPlayersPlayType,
+    pub counting_system: CountingSystemType,
+    pub no_hole_card: bool,
+    pub bonuses: Bonuses,
+    pub custom_index_plays: Vec<IndexPlay>,
+}
+
+impl Default for RulesSchema {
+    fn default() -> Self {
+        Rules::default().into()
+    }
+}
+
+impl From<Rules> for RulesSchema {
+    fn from(rules: Rules) -> Self {
+        Self {
+            version: RULES_SCHEMA_VERSION,
+            game_type: rules.game_type,
+            double_after_split: rules.double_after_split,
+            split_aces: rules.split_aces,
+            surrender: rules.surrender,
+            decks: rules.decks,
+            blackjack_payout: rules.blackjack_payout,
+            is_double_allowed: rules.is_double_allowed,
+            max_splits_allowed: rules.max_splits_allowed,
+            deck_pen: rules.deck_pen,
+            number_of_other_players: rules.number_of_other_players,
+            enable_deviations: rules.enable_deviations,
+            play_variation: rules.play_variation,
+            do_other_players_play_perfect_strategy: rules.do_other_players_play_perfect_strategy,
+            counting_system: rules.counting_system,
+            no_hole_card: rules.no_hole_card,
+            bonuses: rules.bonuses,
+            custom_index_plays: rules.custom_index_plays,
+        }
+    }
+}
+
+impl TryFrom<RulesSchema> for Rules {
+    type Error = RulesError;
+
+    fn try_from(schema: RulesSchema) -> Result<Self, Self::Error> {
+        if schema.version == 0 {
+            return Err(RulesError::MissingSchemaVersion);
+        }
+
+        if schema.version > RULES_SCHEMA_VERSION {
+            return Err(RulesError::UnsupportedSchemaVersion(schema.version));
+        }
+
+        RulesBuilder::new()
+            .game_type(schema.game_type)
+            .double_after_split(schema.double_after_split)
+            .split_aces(schema.split_aces)
+            .surrender(schema.surrender)
+            .decks(schema.decks)
+            .blackjack_payout(schema.blackjack_payout)
+            .is_double_allowed(schema.is_double_allowed)
+            .max_splits_allowed(schema.max_splits_allowed)
+            .deck_pen(schema.deck_pen)
+            .number_of_other_players(schema.number_of_other_players)
+            .enable_deviations(schema.enable_deviations)
+            .play_variation(schema.play_variation)
+            .do_other_players_play_perfect_strategy(schema.do_other_players_play_perfect_strategy)
+            .counting_system(schema.counting_system)
+            .no_hole_card(schema.no_hole_card)
+            .bonuses(schema.bonuses)
+            .custom_index_plays(schema.custom_index_plays)
+            .try_build()
+            .map_err(|mut errors| errors.remove(0))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum Decision {
     #[default]
@@ -397,7 +1194,257 @@ pub enum Decision {
     Split,
     Double,
     Surrender,
-    GotBJ{suited: bool},
+    GotBJ {
+        suited: bool,
+    },
 }
 
 impl Copy for Decision {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_round_trip() {
+        let rules = RulesBuilder::new()
+            .game_type(GameType::Stand17)
+            .double_after_split(false)
+            .split_aces(SplitAces::ReSplitAces)
+            .surrender(true)
+            .decks(2)
+            .blackjack_payout(BlackjackPayout::SixToFive)
+            .is_double_allowed(IsDoubleAllowed::NineTenAndAceOnly)
+            .max_splits_allowed(2)
+            .deck_pen(DeckPen::Custom(1.25))
+            .number_of_other_players(3)
+            .enable_deviations(Deviations::Extended2)
+            .play_variation(PlayVariation::PlayOnlyPositiveTrueCounts)
+            .do_other_players_play_perfect_strategy(OtherPlayersPlayType::NoBustStrategy)
+            .counting_system(CountingSystemType::WongHalves)
+            .build();
+
+        let json = serde_json::to_string(&rules).unwrap();
+        let restored: Rules = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, rules);
+    }
+
+    #[test]
+    fn stable_field_names() {
+        let json = serde_json::to_value(Rules::default()).unwrap();
+
+        assert_eq!(json["version"], RULES_SCHEMA_VERSION);
+        assert_eq!(json["game_type"], "Hit17");
+        assert_eq!(json["decks"], 6);
+        assert_eq!(json["deck_pen"], "One");
+        assert_eq!(json["enable_deviations"], "None");
+        assert_eq!(json["counting_system"], "HiLo");
+    }
+
+    #[test]
+    fn payout_ratios() {
+        assert_eq!(BlackjackPayout::ThreeToTwo.ratio().value(), 1.5);
+        assert_eq!(BlackjackPayout::SixToFive.suited_ratio(), Ratio::new(6, 5));
+        assert_eq!(
+            BlackjackPayout::SixToFiveSuitedTwoToOne.suited_ratio(),
+            Ratio::new(2, 1)
+        );
+
+        assert_eq!(
+            BlackjackPayout::from_ratios(Ratio::new(6, 5), Ratio::new(2, 1)),
+            BlackjackPayout::SixToFiveSuitedTwoToOne
+        );
+        assert_eq!(
+            BlackjackPayout::from_ratios(Ratio::new(7, 5), Ratio::new(7, 5)),
+            BlackjackPayout::Custom {
+                payout: Ratio::new(7, 5),
+                suited: Ratio::new(7, 5)
+            }
+        );
+
+        let bonus = Bonus {
+            mixed: Some(Ratio::new(3, 2)),
+            suited: None,
+        };
+        assert_eq!(bonus.ratio(true), Some(Ratio::new(3, 2)));
+    }
+
+    #[test]
+    fn custom_payouts_and_bonuses_round_trip() {
+        let rules = RulesBuilder::new()
+            .blackjack_payout(BlackjackPayout::Custom {
+                payout: Ratio::new(7, 5),
+                suited: Ratio::new(2, 1),
+            })
+            .bonuses(Bonuses {
+                seven_seven_seven: Bonus {
+                    mixed: Some(Ratio::new(3, 2)),
+                    suited: Some(Ratio::new(2, 1)),
+                },
+                ..Default::default()
+            })
+            .build();
+
+        let json = serde_json::to_string(&rules).unwrap();
+        assert_eq!(serde_json::from_str::<Rules>(&json).unwrap(), rules);
+
+        let errors = RulesBuilder::new()
+            .bonuses(Bonuses {
+                suited_21: Some(Ratio::new(2, 0)),
+                ..Default::default()
+            })
+            .try_build()
+            .unwrap_err();
+        assert_eq!(errors, vec![RulesError::InvalidPayout(Ratio::new(2, 0))]);
+    }
+
+    #[test]
+    fn missing_fields_are_defaulted() {
+        let rules: Rules =
+            serde_json::from_str(r#"{ "version": 1, "decks": 2, "surrender": false }"#).unwrap();
+
+        assert_eq!(rules.decks(), 2);
+        assert!(!rules.surrender());
+        assert_eq!(
+            rules.max_splits_allowed(),
+            Rules::default().max_splits_allowed()
+        );
+    }
+
+    #[test]
+    fn presets() {
+        let strip = RulesBuilder::vegas_strip().build();
+        assert_eq!(strip.decks(), 6);
+        assert_eq!(strip.game_type(), &GameType::Stand17);
+        assert!(strip.double_after_split() && strip.surrender());
+
+        let downtown = RulesBuilder::downtown().build();
+        assert_eq!(downtown.decks(), 2);
+        assert_eq!(downtown.game_type(), &GameType::Hit17);
+
+        let atlantic_city = RulesBuilder::atlantic_city().build();
+        assert_eq!(atlantic_city.decks(), 8);
+        assert!(atlantic_city.surrender());
+
+        let reno = RulesBuilder::reno().build();
+        assert!(!reno.is_double_allowed().double_on_nine());
+        assert!(reno.is_double_allowed().double_on_ten());
+        assert!(reno.is_double_allowed().double_on_eleven());
+
+        assert!(RulesBuilder::european().build().no_hole_card());
+        assert!(RulesBuilder::macau().build().no_hole_card());
+
+        let single_deck = RulesBuilder::single_deck_six_to_five().build();
+        assert_eq!(single_deck.decks(), 1);
+        assert_eq!(single_deck.blackjack_payout(), &BlackjackPayout::SixToFive);
+
+        // the cut card is in front of the first card, so every round starts with a shuffle
+        let online = RulesBuilder::online().build();
+        assert!(online.deck_pen().value() >= 8 * 52);
+    }
+
+    #[test]
+    fn try_build_reports_every_error() {
+        let errors = RulesBuilder::new()
+            .decks(0)
+            .max_splits_allowed(0)
+            .split_aces(SplitAces::ReSplitAces)
+            .number_of_other_players(6)
+            .deck_pen(DeckPen::Custom(-1.0))
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                RulesError::DecksOutOfRange(0),
+                RulesError::TooManyOtherPlayers(6),
+                RulesError::InvalidDeckPen(-1.0),
+                RulesError::SplitAcesWithoutSplits(SplitAces::ReSplitAces),
+            ]
+        );
+
+        let errors = RulesBuilder::new()
+            .decks(2)
+            .max_splits_allowed(5)
+            .deck_pen(DeckPen::Custom(2.5))
+            .try_build()
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                RulesError::TooManySplits(5),
+                RulesError::DeckPenLargerThanShoe {
+                    cards_behind_cut: 130,
+                    total_cards: 104
+                },
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "5 splits allowed, expected at most 3"
+        );
+    }
+
+    #[test]
+    fn try_build_accepts_valid_rules() {
+        for builder in [
+            RulesBuilder::new(),
+            RulesBuilder::vegas_strip(),
+            RulesBuilder::downtown(),
+            RulesBuilder::atlantic_city(),
+            RulesBuilder::reno(),
+            RulesBuilder::european(),
+            RulesBuilder::single_deck_six_to_five(),
+            RulesBuilder::macau(),
+            RulesBuilder::online(),
+        ] {
+            assert_eq!(builder.clone().try_build(), Ok(builder.build()));
+        }
+    }
+
+    #[test]
+    fn build_still_clamps() {
+        let rules = RulesBuilder::new()
+            .decks(0)
+            .max_splits_allowed(9)
+            .number_of_other_players(9)
+            .build();
+
+        assert_eq!(rules.decks(), 1);
+        assert_eq!(rules.max_splits_allowed(), 3);
+        assert_eq!(rules.number_of_other_players(), 4);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let schema: RulesSchema =
+            serde_json::from_str(r#"{ "version": 1, "decks": 20, "max_splits_allowed": 9 }"#)
+                .unwrap();
+
+        assert_eq!(
+            Rules::try_from(schema),
+            Err(RulesError::DecksOutOfRange(20))
+        );
+        assert!(serde_json::from_str::<Rules>(r#"{ "version": 1, "decks": 20 }"#).is_err());
+    }
+
+    #[test]
+    fn schema_version_is_required() {
+        let schema: RulesSchema = serde_json::from_str(r#"{ "decks": 2 }"#).unwrap();
+
+        assert_eq!(
+            Rules::try_from(schema),
+            Err(RulesError::MissingSchemaVersion)
+        );
+    }
+
+    #[test]
+    fn newer_schema_versions_are_rejected() {
+        let json = format!(r#"{{ "version": {} }}"#, RULES_SCHEMA_VERSION + 1);
+
+        assert!(serde_json::from_str::<Rules>(&json).is_err());
+    }
+}
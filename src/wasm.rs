@@ -0,0 +1,366 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    compute_hand, get_decision,
+    hard_totals_chart::hard_totals_chart,
+    index_plays::IndexPlay,
+    insurance::should_i_take_insurance,
+    soft_totals_chart::soft_totals_chart,
+    splits_chart::should_i_split,
+    surrender::should_i_surrender,
+    types::{
+        BlackjackPayout, Bonuses, CountingSystemType, DeckPen, Deviations, GameType,
+        IsDoubleAllowed, OtherPlayersPlayType, PlayVariation, Rules, RulesBuilder, SplitAces,
+    },
+};
+
+/// Rules as a plain JS object. Every field is optional, missing fields take the
+/// `RulesBuilder` defaults and values are clamped the same way the builder does.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsRules {
+    pub game_type: Option<GameType>,
+    pub double_after_split: Option<bool>,
+    pub split_aces: Option<SplitAces>,
+    pub surrender: Option<bool>,
+    pub decks: Option<u8>,
+    pub blackjack_payout: Option<BlackjackPayout>,
+    pub is_double_allowed: Option<IsDoubleAllowed>,
+    pub max_splits_allowed: Option<u8>,
+    pub deck_pen: Option<DeckPen>,
+    pub number_of_other_players: Option<u8>,
+    pub enable_deviations: Option<Deviations>,
+    pub play_variation: Option<PlayVariation>,
+    pub do_other_players_play_perfect_strategy: Option<OtherPlayersPlayType>,
+    pub counting_system: Option<CountingSystemType>,
+    pub no_hole_card: Option<bool>,
+    pub bonuses: Option<Bonuses>,
+    pub custom_index_plays: Option<Vec<IndexPlay>>,
+}
+
+impl JsRules {
+    pub fn into_rules(self) -> Rules {
+        let mut builder = RulesBuilder::new();
+
+        if let Some(val) = self.game_type {
+            builder = builder.game_type(val);
+        }
+        if let Some(val) = self.double_after_split {
+            builder = builder.double_after_split(val);
+        }
+        if let Some(val) = self.split_aces {
+            builder = builder.split_aces(val);
+        }
+        if let Some(val) = self.surrender {
+            builder = builder.surrender(val);
+        }
+        if let Some(val) = self.decks {
+            builder = builder.decks(val);
+        }
+        if let Some(val) = self.blackjack_payout {
+            builder = builder.blackjack_payout(val);
+        }
+        if let Some(val) = self.is_double_allowed {
+            builder = builder.is_double_allowed(val);
+        }
+        if let Some(val) = self.max_splits_allowed {
+            builder = builder.max_splits_allowed(val);
+        }
+        if let Some(val) = self.deck_pen {
+            builder = builder.deck_pen(val);
+        }
+        if let Some(val) = self.number_of_other_players {
+            builder = builder.number_of_other_players(val);
+        }
+        if let Some(val) = self.enable_deviations {
+            builder = builder.enable_deviations(val);
+        }
+        if let Some(val) = self.play_variation {
+            builder = builder.play_variation(val);
+        }
+        if let Some(val) = self.do_other_players_play_perfect_strategy {
+            builder = builder.do_other_players_play_perfect_strategy(val);
+        }
+        if let Some(val) = self.counting_system {
+            builder = builder.counting_system(val);
+        }
+        if let Some(val) = self.no_hole_card {
+            builder = builder.no_hole_card(val);
+        }
+        if let Some(val) = self.bonuses {
+            builder = builder.bonuses(val);
+        }
+        if let Some(val) = self.custom_index_plays {
+            builder = builder.custom_index_plays(val);
+        }
+
+        builder.build()
+    }
+}
+
+impl From<&Rules> for JsRules {
+    fn from(rules: &Rules) -> Self {
+        Self {
+            game_type: Some(rules.game_type().clone()),
+            double_after_split: Some(rules.double_after_split()),
+            split_aces: Some(rules.split_aces().clone()),
+            surrender: Some(rules.surrender()),
+            decks: Some(rules.decks()),
+            blackjack_payout: Some(rules.blackjack_payout().clone()),
+            is_double_allowed: Some(rules.is_double_allowed().clone()),
+            max_splits_allowed: Some(rules.max_splits_allowed()),
+            deck_pen: Some(rules.deck_pen().clone()),
+            number_of_other_players: Some(rules.number_of_other_players()),
+            enable_deviations: Some(rules.deviations().clone()),
+            play_variation: Some(rules.play_variation().clone()),
+            do_other_players_play_perfect_strategy: Some(
+                rules.do_other_players_play_perfect_strategy(),
+            ),
+            counting_system: Some(rules.counting_system()),
+            no_hole_card: Some(rules.no_hole_card()),
+            bonuses: Some(*rules.bonuses()),
+            custom_index_plays: Some(rules.custom_index_plays().to_vec()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandValue {
+    pub total: u8,
+    pub is_blackjack: bool,
+}
+
+impl HandValue {
+    fn of(mut cards: Vec<u8>) -> Self {
+        let is_two_cards = cards.len() == 2;
+        let (total, _) = compute_hand(&mut cards);
+
+        Self {
+            total,
+            is_blackjack: is_two_cards && total == 21,
+        }
+    }
+}
+
+fn rules_from_js(rules: JsValue) -> Result<Rules, JsError> {
+    if rules.is_undefined() || rules.is_null() {
+        return Ok(RulesBuilder::new().build());
+    }
+
+    let rules: JsRules = serde_wasm_bindgen::from_value(rules)?;
+
+    Ok(rules.into_rules())
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    Ok(serde_wasm_bindgen::to_value(value)?)
+}
+
+/// Fills in the defaults and clamps the values of a rules object, the same as `RulesBuilder`
+#[wasm_bindgen(js_name = buildRules)]
+pub fn build_rules(rules: JsValue) -> Result<JsValue, JsError> {
+    to_js(&JsRules::from(&rules_from_js(rules)?))
+}
+
+/// Decision as a string such as `"Hit"`, or `{ GotBJ: { suited } }` for a blackjack
+#[wasm_bindgen(js_name = getDecision)]
+pub fn get_decision_js(
+    cards: Vec<u8>,
+    dealer_up_card: u8,
+    running_count: i32,
+    true_count: f64,
+    rules: JsValue,
+) -> Result<JsValue, JsError> {
+    let rules = rules_from_js(rules)?;
+
+    to_js(&get_decision(
+        &cards,
+        dealer_up_card,
+        running_count as isize,
+        true_count,
+        &rules,
+    ))
+}
+
+/// Decision from the hard totals chart, `null` when the chart doesn't cover the hand
+#[wasm_bindgen(js_name = hardTotalsChart)]
+pub fn hard_totals_chart_js(
+    cards: Vec<u8>,
+    dealer_up_card: u8,
+    running_count: i32,
+    true_count: f64,
+    rules: JsValue,
+) -> Result<JsValue, JsError> {
+    let rules = rules_from_js(rules)?;
+
+    to_js(&hard_totals_chart(
+        &cards,
+        dealer_up_card,
+        running_count as isize,
+        true_count,
+        &rules,
+    ))
+}
+
+/// Decision from the soft totals chart, `null` when the chart doesn't cover the hand
+#[wasm_bindgen(js_name = softTotalsChart)]
+pub fn soft_totals_chart_js(
+    cards: Vec<u8>,
+    dealer_up_card: u8,
+    running_count: i32,
+    true_count: f64,
+    rules: JsValue,
+) -> Result<JsValue, JsError> {
+    let rules = rules_from_js(rules)?;
+
+    to_js(&soft_totals_chart(
+        &cards,
+        dealer_up_card,
+        running_count as isize,
+        true_count,
+        &rules,
+    ))
+}
+
+#[wasm_bindgen(js_name = shouldISplit)]
+pub fn should_i_split_js(
+    cards: Vec<u8>,
+    dealer_up_card: u8,
+    running_count: i32,
+    true_count: f64,
+    rules: JsValue,
+) -> Result<bool, JsError> {
+    let rules = rules_from_js(rules)?;
+
+    Ok(should_i_split(
+        &cards,
+        dealer_up_card,
+        running_count as isize,
+        true_count,
+        &rules,
+    ))
+}
+
+#[wasm_bindgen(js_name = shouldISurrender)]
+pub fn should_i_surrender_js(
+    cards: Vec<u8>,
+    dealer_up_card: u8,
+    running_count: i32,
+    true_count: f64,
+    rules: JsValue,
+) -> Result<bool, JsError> {
+    let rules = rules_from_js(rules)?;
+
+    Ok(should_i_surrender(
+        &cards,
+        dealer_up_card,
+        running_count as isize,
+        true_count,
+        &rules,
+    ))
+}
+
+#[wasm_bindgen(js_name = shouldITakeInsurance)]
+pub fn should_i_take_insurance_js(
+    dealer_up_card: u8,
+    running_count: i32,
+    true_count: f64,
+    rules: JsValue,
+) -> Result<bool, JsError> {
+    let rules = rules_from_js(rules)?;
+
+    Ok(should_i_take_insurance(
+        dealer_up_card,
+        running_count as isize,
+        true_count,
+        &rules,
+    ))
+}
+
+/// `{ total, isBlackjack }` of the hand
+#[wasm_bindgen(js_name = computeHand)]
+pub fn compute_hand_js(cards: Vec<u8>) -> Result<JsValue, JsError> {
+    to_js(&HandValue::of(cards))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_take_builder_defaults() {
+        let rules = JsRules::default().into_rules();
+        let defaults = RulesBuilder::new().build();
+
+        assert_eq!(
+            format!("{:?}", JsRules::from(&rules)),
+            format!("{:?}", JsRules::from(&defaults))
+        );
+    }
+
+    #[test]
+    fn fields_are_applied_and_clamped() {
+        let rules = JsRules {
+            game_type: Some(GameType::Stand17),
+            surrender: Some(true),
+            decks: Some(12),
+            enable_deviations: Some(Deviations::Standard),
+            counting_system: Some(CountingSystemType::Zen),
+            ..Default::default()
+        }
+        .into_rules();
+
+        assert_eq!(rules.game_type(), &GameType::Stand17);
+        assert!(rules.surrender());
+        assert_eq!(rules.decks(), 8);
+        assert_eq!(rules.enable_deviations(), 1);
+        assert_eq!(rules.counting_system(), CountingSystemType::Zen);
+    }
+
+    #[test]
+    fn camel_case_js_objects() {
+        let rules: JsRules = serde_json::from_str(
+            r#"{ "gameType": "Stand17", "doubleAfterSplit": false, "deckPen": { "Custom": 1.5 } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.game_type, Some(GameType::Stand17));
+        assert_eq!(rules.double_after_split, Some(false));
+        assert!(matches!(rules.deck_pen, Some(DeckPen::Custom(pen)) if pen == 1.5));
+        assert_eq!(rules.decks, None);
+
+        let json = serde_json::to_string(&HandValue {
+            total: 21,
+            is_blackjack: true,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"total":21,"isBlackjack":true}"#);
+    }
+
+    #[test]
+    fn hand_values() {
+        assert_eq!(
+            HandValue::of(vec![11, 10]),
+            HandValue {
+                total: 21,
+                is_blackjack: true,
+            }
+        );
+        assert_eq!(
+            HandValue::of(vec![10, 6]),
+            HandValue {
+                total: 16,
+                is_blackjack: false,
+            }
+        );
+        assert_eq!(
+            HandValue::of(vec![7, 4, 10]),
+            HandValue {
+                total: 21,
+                is_blackjack: false,
+            }
+        );
+    }
+}